@@ -10,6 +10,9 @@ CREATE TABLE IF NOT EXISTS users (
     role VARCHAR(64) NOT NULL DEFAULT 'user',
     is_active TINYINT(1) NOT NULL DEFAULT 1,
     profile_picture MEDIUMTEXT,
+    commission_rate DOUBLE,
+    pin_hash TEXT,
+    pin_failed_attempts INT NOT NULL DEFAULT 0,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
 );
@@ -19,6 +22,9 @@ CREATE TABLE IF NOT EXISTS currencies (
     name VARCHAR(255) NOT NULL UNIQUE,
     base INT NOT NULL DEFAULT 0,
     rate DOUBLE NOT NULL DEFAULT 1.0,
+    decimal_places INT,
+    symbol VARCHAR(16),
+    symbol_position VARCHAR(8),
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
 );
@@ -41,13 +47,21 @@ CREATE TABLE IF NOT EXISTS customers (
     address TEXT NOT NULL,
     email TEXT,
     notes TEXT,
+    created_by BIGINT,
+    assigned_user_id BIGINT,
+    search_name VARCHAR(255),
+    search_phone VARCHAR(32),
+    preferred_language VARCHAR(8),
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_customers_search_name (search_name),
+    INDEX idx_customers_search_phone (search_phone)
 );
 
 CREATE TABLE IF NOT EXISTS unit_groups (
     id BIGINT PRIMARY KEY AUTO_INCREMENT,
     name VARCHAR(255) NOT NULL UNIQUE,
+    quantity_decimals INT,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
 );
@@ -67,13 +81,23 @@ CREATE TABLE IF NOT EXISTS products (
     id BIGINT PRIMARY KEY AUTO_INCREMENT,
     name TEXT NOT NULL,
     description TEXT,
-    price DOUBLE,
+    price DECIMAL(18,4),
     currency_id BIGINT,
     supplier_id BIGINT,
     stock_quantity DOUBLE,
     unit TEXT,
     image_path TEXT,
     bar_code TEXT,
+    category VARCHAR(100),
+    generic_name VARCHAR(255),
+    strength VARCHAR(100),
+    dosage_form VARCHAR(100),
+    is_controlled TINYINT NOT NULL DEFAULT 0,
+    weight_kg DOUBLE,
+    volume_l DOUBLE,
+    batch_strategy VARCHAR(8),
+    min_stock_level DOUBLE,
+    reorder_quantity DOUBLE,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (currency_id) REFERENCES currencies(id),
@@ -86,9 +110,10 @@ CREATE TABLE IF NOT EXISTS purchases (
     date TEXT NOT NULL,
     notes TEXT,
     currency_id BIGINT,
-    total_amount DOUBLE NOT NULL DEFAULT 0,
-    additional_cost DOUBLE NOT NULL DEFAULT 0,
+    total_amount DECIMAL(18,4) NOT NULL DEFAULT 0,
+    additional_cost DECIMAL(18,4) NOT NULL DEFAULT 0,
     batch_number TEXT,
+    order_date VARCHAR(32),
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (supplier_id) REFERENCES suppliers(id),
@@ -100,14 +125,16 @@ CREATE TABLE IF NOT EXISTS purchase_items (
     purchase_id BIGINT NOT NULL,
     product_id BIGINT NOT NULL,
     unit_id BIGINT NOT NULL,
-    per_price DOUBLE NOT NULL,
+    per_price DECIMAL(18,6) NOT NULL,
     amount DOUBLE NOT NULL,
-    total DOUBLE NOT NULL,
+    total DECIMAL(18,4) NOT NULL,
     per_unit DOUBLE,
-    cost_price DOUBLE,
-    wholesale_price DOUBLE,
-    retail_price DOUBLE,
+    cost_price DECIMAL(18,6),
+    wholesale_price DECIMAL(18,4),
+    retail_price DECIMAL(18,4),
     expiry_date TEXT,
+    weight DOUBLE,
+    warehouse_id BIGINT,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (purchase_id) REFERENCES purchases(id) ON DELETE CASCADE,
     FOREIGN KEY (product_id) REFERENCES products(id),
@@ -118,7 +145,7 @@ CREATE TABLE IF NOT EXISTS purchase_additional_costs (
     id BIGINT PRIMARY KEY AUTO_INCREMENT,
     purchase_id BIGINT NOT NULL,
     name TEXT NOT NULL,
-    amount DOUBLE NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (purchase_id) REFERENCES purchases(id) ON DELETE CASCADE
 );
@@ -142,8 +169,8 @@ CREATE TABLE IF NOT EXISTS accounts (
     coa_category_id BIGINT,
     account_code VARCHAR(255) UNIQUE,
     account_type TEXT,
-    initial_balance DOUBLE NOT NULL DEFAULT 0,
-    current_balance DOUBLE NOT NULL DEFAULT 0,
+    initial_balance DECIMAL(18,4) NOT NULL DEFAULT 0,
+    current_balance DECIMAL(18,4) NOT NULL DEFAULT 0,
     is_active INT NOT NULL DEFAULT 1,
     notes TEXT,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
@@ -156,10 +183,10 @@ CREATE TABLE IF NOT EXISTS purchase_payments (
     id BIGINT PRIMARY KEY AUTO_INCREMENT,
     purchase_id BIGINT NOT NULL,
     account_id BIGINT,
-    amount DOUBLE NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
     currency TEXT NOT NULL,
     rate DOUBLE NOT NULL,
-    total DOUBLE NOT NULL,
+    total DECIMAL(18,4) NOT NULL,
     date TEXT NOT NULL,
     notes TEXT,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
@@ -174,16 +201,20 @@ CREATE TABLE IF NOT EXISTS sales (
     notes TEXT,
     currency_id BIGINT,
     exchange_rate DOUBLE NOT NULL DEFAULT 1,
-    total_amount DOUBLE NOT NULL DEFAULT 0,
-    base_amount DOUBLE NOT NULL DEFAULT 0,
-    paid_amount DOUBLE NOT NULL DEFAULT 0,
-    additional_cost DOUBLE NOT NULL DEFAULT 0,
+    total_amount DECIMAL(18,4) NOT NULL DEFAULT 0,
+    base_amount DECIMAL(18,4) NOT NULL DEFAULT 0,
+    paid_amount DECIMAL(18,4) NOT NULL DEFAULT 0,
+    additional_cost DECIMAL(18,4) NOT NULL DEFAULT 0,
     order_discount_type TEXT,
-    order_discount_value DOUBLE NOT NULL DEFAULT 0,
-    order_discount_amount DOUBLE NOT NULL DEFAULT 0,
+    order_discount_value DECIMAL(18,4) NOT NULL DEFAULT 0,
+    order_discount_amount DECIMAL(18,4) NOT NULL DEFAULT 0,
     discount_code_id BIGINT,
+    created_by BIGINT,
+    commission_mode VARCHAR(16),
+    invoice_number VARCHAR(64),
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_sales_invoice_number (invoice_number),
     FOREIGN KEY (customer_id) REFERENCES customers(id),
     FOREIGN KEY (currency_id) REFERENCES currencies(id)
 );
@@ -193,13 +224,15 @@ CREATE TABLE IF NOT EXISTS sale_items (
     sale_id BIGINT NOT NULL,
     product_id BIGINT NOT NULL,
     unit_id BIGINT NOT NULL,
-    per_price DOUBLE NOT NULL,
+    per_price DECIMAL(18,6) NOT NULL,
     amount DOUBLE NOT NULL,
-    total DOUBLE NOT NULL,
+    total DECIMAL(18,4) NOT NULL,
     purchase_item_id BIGINT,
     sale_type TEXT,
     discount_type TEXT,
-    discount_value DOUBLE NOT NULL DEFAULT 0,
+    discount_value DECIMAL(18,4) NOT NULL DEFAULT 0,
+    sale_bundle_id BIGINT,
+    warehouse_id BIGINT,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (sale_id) REFERENCES sales(id) ON DELETE CASCADE,
     FOREIGN KEY (product_id) REFERENCES products(id),
@@ -213,9 +246,11 @@ CREATE TABLE IF NOT EXISTS sale_payments (
     account_id BIGINT,
     currency_id BIGINT,
     exchange_rate DOUBLE NOT NULL DEFAULT 1,
-    amount DOUBLE NOT NULL,
-    base_amount DOUBLE NOT NULL DEFAULT 0,
+    amount DECIMAL(18,4) NOT NULL,
+    base_amount DECIMAL(18,4) NOT NULL DEFAULT 0,
     date TEXT NOT NULL,
+    write_off_id BIGINT,
+    sale_return_id BIGINT,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (sale_id) REFERENCES sales(id) ON DELETE CASCADE,
     FOREIGN KEY (account_id) REFERENCES accounts(id),
@@ -226,7 +261,7 @@ CREATE TABLE IF NOT EXISTS sale_additional_costs (
     id BIGINT PRIMARY KEY AUTO_INCREMENT,
     sale_id BIGINT NOT NULL,
     name TEXT NOT NULL,
-    amount DOUBLE NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (sale_id) REFERENCES sales(id) ON DELETE CASCADE
 );
@@ -234,7 +269,7 @@ CREATE TABLE IF NOT EXISTS sale_additional_costs (
 CREATE TABLE IF NOT EXISTS services (
     id BIGINT PRIMARY KEY AUTO_INCREMENT,
     name VARCHAR(255) NOT NULL,
-    price DOUBLE NOT NULL DEFAULT 0,
+    price DECIMAL(18,4) NOT NULL DEFAULT 0,
     currency_id BIGINT,
     description TEXT,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
@@ -247,11 +282,11 @@ CREATE TABLE IF NOT EXISTS sale_service_items (
     sale_id BIGINT NOT NULL,
     service_id BIGINT NOT NULL,
     name TEXT NOT NULL,
-    price DOUBLE NOT NULL,
+    price DECIMAL(18,4) NOT NULL,
     quantity DOUBLE NOT NULL DEFAULT 1,
-    total DOUBLE NOT NULL,
+    total DECIMAL(18,4) NOT NULL,
     discount_type TEXT,
-    discount_value DOUBLE NOT NULL DEFAULT 0,
+    discount_value DECIMAL(18,4) NOT NULL DEFAULT 0,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (sale_id) REFERENCES sales(id) ON DELETE CASCADE,
     FOREIGN KEY (service_id) REFERENCES services(id)
@@ -261,8 +296,8 @@ CREATE TABLE IF NOT EXISTS sale_discount_codes (
     id BIGINT PRIMARY KEY AUTO_INCREMENT,
     code VARCHAR(255) NOT NULL UNIQUE,
     type VARCHAR(32) NOT NULL,
-    value DOUBLE NOT NULL DEFAULT 0,
-    min_purchase DOUBLE NOT NULL DEFAULT 0,
+    value DECIMAL(18,4) NOT NULL DEFAULT 0,
+    min_purchase DECIMAL(18,4) NOT NULL DEFAULT 0,
     valid_from TEXT,
     valid_to TEXT,
     max_uses INT,
@@ -270,6 +305,43 @@ CREATE TABLE IF NOT EXISTS sale_discount_codes (
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP
 );
 
+CREATE TABLE IF NOT EXISTS product_bundles (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    name TEXT NOT NULL,
+    bar_code TEXT,
+    price DECIMAL(18,4) NOT NULL DEFAULT 0,
+    currency_id BIGINT,
+    is_active INT NOT NULL DEFAULT 1,
+    notes TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (currency_id) REFERENCES currencies(id)
+);
+
+CREATE TABLE IF NOT EXISTS product_bundle_items (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    bundle_id BIGINT NOT NULL,
+    product_id BIGINT NOT NULL,
+    unit_id BIGINT NOT NULL,
+    quantity DOUBLE NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (bundle_id) REFERENCES product_bundles(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id) REFERENCES products(id),
+    FOREIGN KEY (unit_id) REFERENCES units(id)
+);
+
+CREATE TABLE IF NOT EXISTS sale_bundles (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    sale_id BIGINT NOT NULL,
+    bundle_id BIGINT NOT NULL,
+    quantity DOUBLE NOT NULL DEFAULT 1,
+    price DECIMAL(18,4) NOT NULL,
+    total DECIMAL(18,4) NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (sale_id) REFERENCES sales(id) ON DELETE CASCADE,
+    FOREIGN KEY (bundle_id) REFERENCES product_bundles(id)
+);
+
 CREATE TABLE IF NOT EXISTS expense_types (
     id BIGINT PRIMARY KEY AUTO_INCREMENT,
     name VARCHAR(255) NOT NULL UNIQUE,
@@ -281,13 +353,14 @@ CREATE TABLE IF NOT EXISTS expenses (
     id BIGINT PRIMARY KEY AUTO_INCREMENT,
     expense_type_id BIGINT NOT NULL,
     account_id BIGINT,
-    amount DOUBLE NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
     currency TEXT NOT NULL,
     rate DOUBLE NOT NULL DEFAULT 1.0,
-    total DOUBLE NOT NULL,
+    total DECIMAL(18,4) NOT NULL,
     date TEXT NOT NULL,
     bill_no TEXT,
     description TEXT,
+    asset_id BIGINT,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (expense_type_id) REFERENCES expense_types(id),
@@ -302,7 +375,7 @@ CREATE TABLE IF NOT EXISTS employees (
     address TEXT NOT NULL,
     position TEXT,
     hire_date TEXT,
-    base_salary DOUBLE,
+    base_salary DECIMAL(18,4),
     photo_path TEXT,
     notes TEXT,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
@@ -314,9 +387,10 @@ CREATE TABLE IF NOT EXISTS salaries (
     employee_id BIGINT NOT NULL,
     year INT NOT NULL,
     month TEXT NOT NULL,
-    amount DOUBLE NOT NULL,
-    deductions DOUBLE NOT NULL DEFAULT 0,
+    amount DECIMAL(18,4) NOT NULL,
+    deductions DECIMAL(18,4) NOT NULL DEFAULT 0,
     notes TEXT,
+    amount_enc TEXT,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (employee_id) REFERENCES employees(id) ON DELETE CASCADE,
@@ -330,7 +404,7 @@ CREATE TABLE IF NOT EXISTS deductions (
     month VARCHAR(255) NOT NULL DEFAULT 'حمل',
     currency TEXT NOT NULL,
     rate DOUBLE NOT NULL DEFAULT 1.0,
-    amount DOUBLE NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (employee_id) REFERENCES employees(id) ON DELETE CASCADE
@@ -352,7 +426,7 @@ CREATE TABLE IF NOT EXISTS account_currency_balances (
     id BIGINT PRIMARY KEY AUTO_INCREMENT,
     account_id BIGINT NOT NULL,
     currency_id BIGINT NOT NULL,
-    balance DOUBLE NOT NULL DEFAULT 0,
+    balance DECIMAL(18,4) NOT NULL DEFAULT 0,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE,
     FOREIGN KEY (currency_id) REFERENCES currencies(id),
@@ -375,10 +449,10 @@ CREATE TABLE IF NOT EXISTS journal_entry_lines (
     journal_entry_id BIGINT NOT NULL,
     account_id BIGINT NOT NULL,
     currency_id BIGINT NOT NULL,
-    debit_amount DOUBLE NOT NULL DEFAULT 0,
-    credit_amount DOUBLE NOT NULL DEFAULT 0,
+    debit_amount DECIMAL(18,4) NOT NULL DEFAULT 0,
+    credit_amount DECIMAL(18,4) NOT NULL DEFAULT 0,
     exchange_rate DOUBLE NOT NULL DEFAULT 1,
-    base_amount DOUBLE NOT NULL DEFAULT 0,
+    base_amount DECIMAL(18,4) NOT NULL DEFAULT 0,
     description TEXT,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (journal_entry_id) REFERENCES journal_entries(id) ON DELETE CASCADE,
@@ -401,10 +475,10 @@ CREATE TABLE IF NOT EXISTS account_transactions (
     id BIGINT PRIMARY KEY AUTO_INCREMENT,
     account_id BIGINT NOT NULL,
     transaction_type TEXT NOT NULL,
-    amount DOUBLE NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
     currency TEXT NOT NULL,
     rate DOUBLE NOT NULL,
-    total DOUBLE NOT NULL,
+    total DECIMAL(18,4) NOT NULL,
     transaction_date TEXT NOT NULL,
     is_full INT NOT NULL DEFAULT 0,
     notes TEXT,
@@ -413,6 +487,897 @@ CREATE TABLE IF NOT EXISTS account_transactions (
     FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
 );
 
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    action VARCHAR(64) NOT NULL,
+    entity_type VARCHAR(64) NOT NULL,
+    entity_id BIGINT,
+    details TEXT,
+    user_id BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_audit_log_entity (entity_type, entity_id)
+);
+
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    name TEXT NOT NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    token_prefix VARCHAR(16) NOT NULL,
+    scopes TEXT NOT NULL,
+    expires_at TEXT,
+    rate_limit_per_minute INT NOT NULL DEFAULT 60,
+    revoked INT NOT NULL DEFAULT 0,
+    last_used_at DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS app_settings (
+    setting_key VARCHAR(191) PRIMARY KEY,
+    value TEXT,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS sql_console_history (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    user_id BIGINT NOT NULL,
+    sql_text TEXT NOT NULL,
+    read_only TINYINT NOT NULL DEFAULT 1,
+    success TINYINT NOT NULL DEFAULT 1,
+    error TEXT,
+    row_count BIGINT,
+    duration_ms BIGINT NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_sql_console_history_user (user_id, created_at)
+);
+
+CREATE TABLE IF NOT EXISTS product_price_history (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    product_id BIGINT NOT NULL,
+    field VARCHAR(32) NOT NULL,
+    old_value DECIMAL(18,6),
+    new_value DECIMAL(18,6),
+    source VARCHAR(32) NOT NULL,
+    reference_id BIGINT,
+    user_id BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_product_price_history_product (product_id, field, id),
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS margin_guard_log (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    sale_id BIGINT NOT NULL,
+    product_id BIGINT NOT NULL,
+    purchase_item_id BIGINT,
+    unit_price DECIMAL(18,6) NOT NULL,
+    unit_cost DECIMAL(18,6) NOT NULL,
+    margin_percent DOUBLE NOT NULL,
+    mode VARCHAR(16) NOT NULL,
+    override_reason TEXT,
+    user_id BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_margin_guard_log_sale (sale_id)
+);
+
+CREATE TABLE IF NOT EXISTS pricing_rules (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    scope VARCHAR(16) NOT NULL,
+    product_id BIGINT,
+    category VARCHAR(100),
+    retail_markup_percent DOUBLE NOT NULL DEFAULT 0,
+    wholesale_markup_percent DOUBLE NOT NULL DEFAULT 0,
+    rounding_step DOUBLE NOT NULL DEFAULT 0,
+    rounding_mode VARCHAR(16) NOT NULL DEFAULT 'nearest',
+    is_active TINYINT NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_pricing_rules_product (product_id),
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS restaurant_zones (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(100) NOT NULL,
+    sort_order INT NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS restaurant_tables (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    zone_id BIGINT,
+    name VARCHAR(100) NOT NULL,
+    seats INT NOT NULL DEFAULT 4,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (zone_id) REFERENCES restaurant_zones(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS kitchen_printers (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(100) NOT NULL,
+    printer_ip VARCHAR(100) NOT NULL,
+    printer_port INT NOT NULL DEFAULT 9100,
+    is_default TINYINT NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS kitchen_printer_routes (
+    category VARCHAR(100) PRIMARY KEY,
+    printer_id BIGINT NOT NULL,
+    FOREIGN KEY (printer_id) REFERENCES kitchen_printers(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS order_tickets (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    table_id BIGINT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'open',
+    notes TEXT,
+    opened_by BIGINT,
+    sale_id BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    closed_at DATETIME,
+    INDEX idx_order_tickets_table (table_id, status),
+    FOREIGN KEY (table_id) REFERENCES restaurant_tables(id)
+);
+
+CREATE TABLE IF NOT EXISTS order_ticket_items (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    ticket_id BIGINT NOT NULL,
+    product_id BIGINT NOT NULL,
+    unit_id BIGINT NOT NULL,
+    quantity DOUBLE NOT NULL,
+    per_price DECIMAL(18,6) NOT NULL,
+    notes TEXT,
+    status VARCHAR(16) NOT NULL DEFAULT 'new',
+    printed_at DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (ticket_id) REFERENCES order_tickets(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id) REFERENCES products(id)
+);
+
+CREATE TABLE IF NOT EXISTS assets (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(255) NOT NULL,
+    category VARCHAR(32) NOT NULL,
+    identifier VARCHAR(100),
+    purchase_date VARCHAR(10) NOT NULL,
+    purchase_cost DECIMAL(18,4) NOT NULL,
+    salvage_value DECIMAL(18,4) NOT NULL DEFAULT 0,
+    useful_life_months INT NOT NULL,
+    depreciation_method VARCHAR(32) NOT NULL DEFAULT 'straight_line',
+    expense_account_id BIGINT,
+    accumulated_account_id BIGINT,
+    status VARCHAR(16) NOT NULL DEFAULT 'active',
+    notes TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (expense_account_id) REFERENCES accounts(id) ON DELETE SET NULL,
+    FOREIGN KEY (accumulated_account_id) REFERENCES accounts(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS asset_depreciation_entries (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    asset_id BIGINT NOT NULL,
+    period VARCHAR(7) NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
+    journal_entry_id BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_asset_depreciation_period (asset_id, period),
+    FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS shipments (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    reference VARCHAR(100) NOT NULL,
+    description TEXT,
+    allocation_method VARCHAR(16) NOT NULL DEFAULT 'value',
+    status VARCHAR(16) NOT NULL DEFAULT 'open',
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    closed_at DATETIME
+);
+
+CREATE TABLE IF NOT EXISTS shipment_purchases (
+    shipment_id BIGINT NOT NULL,
+    purchase_id BIGINT NOT NULL PRIMARY KEY,
+    FOREIGN KEY (shipment_id) REFERENCES shipments(id) ON DELETE CASCADE,
+    FOREIGN KEY (purchase_id) REFERENCES purchases(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS shipment_costs (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    shipment_id BIGINT NOT NULL,
+    cost_type VARCHAR(16) NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
+    currency_id BIGINT,
+    exchange_rate DOUBLE NOT NULL DEFAULT 1,
+    notes TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (shipment_id) REFERENCES shipments(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS shipment_allocations (
+    shipment_id BIGINT NOT NULL,
+    purchase_item_id BIGINT NOT NULL,
+    allocated_cost DECIMAL(18,4) NOT NULL,
+    landed_unit_cost DECIMAL(18,6) NOT NULL,
+    previous_cost_price DECIMAL(18,6),
+    PRIMARY KEY (shipment_id, purchase_item_id),
+    FOREIGN KEY (shipment_id) REFERENCES shipments(id) ON DELETE CASCADE,
+    FOREIGN KEY (purchase_item_id) REFERENCES purchase_items(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS branch_transfers (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    direction VARCHAR(8) NOT NULL,
+    transfer_key VARCHAR(191) NOT NULL,
+    branch VARCHAR(255) NOT NULL,
+    sale_id BIGINT,
+    purchase_id BIGINT,
+    payload LONGTEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_branch_transfer (direction, transfer_key)
+);
+
+CREATE TABLE IF NOT EXISTS approval_rules (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    operation VARCHAR(32) NOT NULL,
+    threshold DOUBLE NOT NULL DEFAULT 0,
+    approver_role VARCHAR(50) NOT NULL DEFAULT 'admin',
+    is_active TINYINT NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS approval_requests (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    rule_id BIGINT,
+    operation VARCHAR(32) NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
+    payload LONGTEXT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    approver_role VARCHAR(50) NOT NULL,
+    requested_by BIGINT,
+    decided_by BIGINT,
+    decision_note TEXT,
+    result_id BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    decided_at DATETIME,
+    INDEX idx_approval_requests_status (status),
+    FOREIGN KEY (rule_id) REFERENCES approval_rules(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS daily_summaries (
+    business_date DATE PRIMARY KEY,
+    sales_count BIGINT NOT NULL DEFAULT 0,
+    sales_total DECIMAL(18,4) NOT NULL DEFAULT 0,
+    payments_received DECIMAL(18,4) NOT NULL DEFAULT 0,
+    purchases_total DECIMAL(18,4) NOT NULL DEFAULT 0,
+    expenses_total DECIMAL(18,4) NOT NULL DEFAULT 0,
+    cash_balance DECIMAL(18,4) NOT NULL DEFAULT 0,
+    stock_value DECIMAL(18,4) NOT NULL DEFAULT 0,
+    receivables DECIMAL(18,4) NOT NULL DEFAULT 0,
+    is_closed TINYINT NOT NULL DEFAULT 1,
+    closed_by BIGINT,
+    closed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS account_export_mappings (
+    account_id BIGINT PRIMARY KEY,
+    external_code VARCHAR(100),
+    external_name VARCHAR(255),
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS document_templates (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(255) NOT NULL,
+    kind VARCHAR(16) NOT NULL,
+    target VARCHAR(16) NOT NULL,
+    body MEDIUMTEXT NOT NULL,
+    is_default TINYINT NOT NULL DEFAULT 0,
+    language VARCHAR(8),
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS print_jobs (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    kind VARCHAR(16) NOT NULL DEFAULT 'receipt',
+    reference_id BIGINT,
+    printer_ip VARCHAR(100) NOT NULL,
+    printer_port INT NOT NULL DEFAULT 9100,
+    content MEDIUMTEXT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts INT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    printed_at DATETIME,
+    INDEX idx_print_jobs_status (status, next_attempt_at)
+);
+
+CREATE TABLE IF NOT EXISTS product_deposits (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    product_id BIGINT NOT NULL UNIQUE,
+    name VARCHAR(255) NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
+    containers_per_unit DOUBLE NOT NULL DEFAULT 1,
+    is_active TINYINT NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS customer_containers (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    customer_id BIGINT NOT NULL,
+    deposit_id BIGINT NOT NULL,
+    sale_id BIGINT,
+    quantity DOUBLE NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
+    date VARCHAR(32) NOT NULL,
+    notes TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_customer_containers_customer (customer_id, deposit_id),
+    FOREIGN KEY (customer_id) REFERENCES customers(id),
+    FOREIGN KEY (deposit_id) REFERENCES product_deposits(id),
+    FOREIGN KEY (sale_id) REFERENCES sales(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS product_barcodes (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    product_id BIGINT NOT NULL,
+    barcode VARCHAR(128) NOT NULL UNIQUE,
+    unit_id BIGINT NOT NULL,
+    quantity DOUBLE NOT NULL DEFAULT 1,
+    label VARCHAR(255),
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE,
+    FOREIGN KEY (unit_id) REFERENCES units(id)
+);
+
+CREATE TABLE IF NOT EXISTS purchase_cost_adjustments (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    purchase_id BIGINT NOT NULL,
+    purchase_item_id BIGINT NOT NULL,
+    product_id BIGINT NOT NULL,
+    old_per_price DECIMAL(18,6) NOT NULL,
+    new_per_price DECIMAL(18,6) NOT NULL,
+    sold_ratio DOUBLE NOT NULL,
+    cogs_delta DECIMAL(18,4) NOT NULL,
+    inventory_delta DECIMAL(18,4) NOT NULL,
+    journal_entry_id BIGINT,
+    reason TEXT,
+    adjusted_by BIGINT,
+    date VARCHAR(32) NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_purchase_cost_adjustments_purchase (purchase_id),
+    FOREIGN KEY (purchase_id) REFERENCES purchases(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS employee_documents (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    employee_id BIGINT NOT NULL,
+    doc_type VARCHAR(32) NOT NULL,
+    document_number VARCHAR(128),
+    issue_date DATE,
+    expiry_date DATE,
+    file_path TEXT,
+    notes TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_employee_documents_employee (employee_id),
+    INDEX idx_employee_documents_expiry (expiry_date),
+    FOREIGN KEY (employee_id) REFERENCES employees(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS commission_entries (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    user_id BIGINT NOT NULL,
+    sale_id BIGINT NOT NULL,
+    sale_payment_id BIGINT,
+    source VARCHAR(16) NOT NULL,
+    basis DECIMAL(18,4) NOT NULL,
+    rate DOUBLE NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
+    date VARCHAR(32) NOT NULL,
+    settled_at DATETIME,
+    salary_id BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_commission_entries_user (user_id, date),
+    INDEX idx_commission_entries_sale (sale_id),
+    INDEX idx_commission_entries_payment (sale_payment_id)
+);
+
+CREATE TABLE IF NOT EXISTS payroll_runs (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    year INT NOT NULL,
+    month VARCHAR(64) NOT NULL,
+    account_id BIGINT,
+    status VARCHAR(16) NOT NULL DEFAULT 'draft',
+    notes TEXT,
+    created_by BIGINT,
+    reviewed_by BIGINT,
+    reviewed_at DATETIME,
+    approved_by BIGINT,
+    approved_at DATETIME,
+    posted_date VARCHAR(32),
+    journal_entry_id BIGINT,
+    rejection_note TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_payroll_runs_period (year, month)
+);
+
+CREATE TABLE IF NOT EXISTS payroll_run_lines (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    run_id BIGINT NOT NULL,
+    employee_id BIGINT NOT NULL,
+    base_salary DECIMAL(18,4) NOT NULL DEFAULT 0,
+    deductions DECIMAL(18,4) NOT NULL DEFAULT 0,
+    adjustment DECIMAL(18,4) NOT NULL DEFAULT 0,
+    adjustment_note TEXT,
+    salary_id BIGINT,
+    INDEX idx_payroll_run_lines_run (run_id),
+    FOREIGN KEY (run_id) REFERENCES payroll_runs(id) ON DELETE CASCADE,
+    FOREIGN KEY (employee_id) REFERENCES employees(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS cost_variance_log (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    purchase_id BIGINT NOT NULL,
+    product_id BIGINT NOT NULL,
+    unit_price DECIMAL(18,6) NOT NULL,
+    average_cost DECIMAL(18,6) NOT NULL,
+    deviation_percent DOUBLE NOT NULL,
+    mode VARCHAR(16) NOT NULL,
+    approval_id BIGINT,
+    user_id BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_cost_variance_log_purchase (purchase_id)
+);
+
+CREATE TABLE IF NOT EXISTS backorders (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    customer_id BIGINT NOT NULL,
+    product_id BIGINT NOT NULL,
+    unit_id BIGINT NOT NULL,
+    quantity DOUBLE NOT NULL,
+    per_price DECIMAL(18,6) NOT NULL,
+    date VARCHAR(32) NOT NULL,
+    notes TEXT,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    sale_id BIGINT,
+    created_by BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    fulfilled_at DATETIME,
+    INDEX idx_backorders_status (status, date),
+    FOREIGN KEY (customer_id) REFERENCES customers(id),
+    FOREIGN KEY (product_id) REFERENCES products(id)
+);
+
+CREATE TABLE IF NOT EXISTS csv_import_templates (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(255) NOT NULL,
+    target VARCHAR(32) NOT NULL,
+    delimiter VARCHAR(8) NOT NULL DEFAULT ',',
+    has_header TINYINT NOT NULL DEFAULT 1,
+    skip_rows INT NOT NULL DEFAULT 0,
+    date_format VARCHAR(16) NOT NULL DEFAULT 'YYYY-MM-DD',
+    decimal_separator VARCHAR(1) NOT NULL DEFAULT '.',
+    mappings TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS holidays (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    holiday_date DATE NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    recurring TINYINT NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_holidays_date (holiday_date)
+);
+
+CREATE TABLE IF NOT EXISTS collection_contacts (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    customer_id BIGINT NOT NULL,
+    contact_at DATETIME NOT NULL,
+    method VARCHAR(16) NOT NULL,
+    outcome VARCHAR(16) NOT NULL,
+    notes TEXT,
+    promised_amount DECIMAL(18,4),
+    promised_date DATE,
+    user_id BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_collection_contacts_customer (customer_id, contact_at),
+    INDEX idx_collection_contacts_promise (promised_date),
+    FOREIGN KEY (customer_id) REFERENCES customers(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS sales_targets (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    scope VARCHAR(16) NOT NULL,
+    user_id BIGINT,
+    category VARCHAR(100),
+    period VARCHAR(8) NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
+    accelerator DOUBLE,
+    notes TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    INDEX idx_sales_targets_period (period),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS shipping_rules (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(255) NOT NULL,
+    zone VARCHAR(100),
+    method VARCHAR(16) NOT NULL,
+    rate DECIMAL(18,6) NOT NULL DEFAULT 0,
+    min_charge DECIMAL(18,4) NOT NULL DEFAULT 0,
+    free_over DECIMAL(18,4),
+    is_active TINYINT NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_shipping_rules_zone (zone)
+);
+
+CREATE TABLE IF NOT EXISTS receivable_write_offs (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    customer_id BIGINT NOT NULL,
+    kind VARCHAR(16) NOT NULL DEFAULT 'bad_debt',
+    amount DECIMAL(18,4) NOT NULL,
+    reason TEXT NOT NULL,
+    date VARCHAR(32) NOT NULL,
+    journal_entry_id BIGINT,
+    approval_id BIGINT,
+    user_id BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_receivable_write_offs_customer (customer_id),
+    FOREIGN KEY (customer_id) REFERENCES customers(id)
+);
+
+CREATE TABLE IF NOT EXISTS permissions (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    role VARCHAR(64),
+    user_id BIGINT,
+    command VARCHAR(100) NOT NULL,
+    allowed TINYINT NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_permissions_role (role),
+    INDEX idx_permissions_user (user_id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS currency_exchanges (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    account_id BIGINT NOT NULL,
+    from_currency_id BIGINT NOT NULL,
+    to_currency_id BIGINT NOT NULL,
+    amount_from DECIMAL(18,4) NOT NULL,
+    amount_to DECIMAL(18,4) NOT NULL,
+    rate DOUBLE NOT NULL,
+    official_rate DOUBLE NOT NULL,
+    gain_loss DECIMAL(18,4) NOT NULL DEFAULT 0,
+    date VARCHAR(32) NOT NULL,
+    notes TEXT,
+    out_transaction_id BIGINT,
+    in_transaction_id BIGINT,
+    journal_entry_id BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_currency_exchanges_account (account_id, date),
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (from_currency_id) REFERENCES currencies(id),
+    FOREIGN KEY (to_currency_id) REFERENCES currencies(id)
+);
+
+CREATE TABLE IF NOT EXISTS purchase_returns (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    purchase_id BIGINT NOT NULL,
+    supplier_id BIGINT NOT NULL,
+    date VARCHAR(32) NOT NULL,
+    currency_id BIGINT,
+    exchange_rate DOUBLE NOT NULL DEFAULT 1,
+    total_amount DECIMAL(18,4) NOT NULL DEFAULT 0,
+    notes TEXT,
+    journal_entry_id BIGINT,
+    created_by BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_purchase_returns_supplier (supplier_id),
+    FOREIGN KEY (purchase_id) REFERENCES purchases(id) ON DELETE CASCADE,
+    FOREIGN KEY (supplier_id) REFERENCES suppliers(id)
+);
+
+CREATE TABLE IF NOT EXISTS purchase_return_items (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    return_id BIGINT NOT NULL,
+    purchase_item_id BIGINT NOT NULL,
+    product_id BIGINT NOT NULL,
+    unit_id BIGINT NOT NULL,
+    quantity DOUBLE NOT NULL,
+    per_price DECIMAL(18,6) NOT NULL,
+    total DECIMAL(18,4) NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_purchase_return_items_batch (purchase_item_id),
+    FOREIGN KEY (return_id) REFERENCES purchase_returns(id) ON DELETE CASCADE,
+    FOREIGN KEY (purchase_item_id) REFERENCES purchase_items(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id) REFERENCES products(id),
+    FOREIGN KEY (unit_id) REFERENCES units(id)
+);
+
+CREATE TABLE IF NOT EXISTS sale_returns (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    credit_note_number VARCHAR(32) NOT NULL UNIQUE,
+    sale_id BIGINT NOT NULL,
+    customer_id BIGINT NOT NULL,
+    date VARCHAR(32) NOT NULL,
+    currency_id BIGINT,
+    exchange_rate DOUBLE NOT NULL DEFAULT 1,
+    total_amount DECIMAL(18,4) NOT NULL DEFAULT 0,
+    base_amount DECIMAL(18,4) NOT NULL DEFAULT 0,
+    reason TEXT,
+    journal_entry_id BIGINT,
+    created_by BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_sale_returns_customer (customer_id),
+    FOREIGN KEY (sale_id) REFERENCES sales(id) ON DELETE CASCADE,
+    FOREIGN KEY (customer_id) REFERENCES customers(id)
+);
+
+CREATE TABLE IF NOT EXISTS sale_return_items (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    return_id BIGINT NOT NULL,
+    sale_item_id BIGINT NOT NULL,
+    product_id BIGINT NOT NULL,
+    unit_id BIGINT NOT NULL,
+    purchase_item_id BIGINT,
+    quantity DOUBLE NOT NULL,
+    per_price DECIMAL(18,6) NOT NULL,
+    total DECIMAL(18,4) NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_sale_return_items_sale_item (sale_item_id),
+    INDEX idx_sale_return_items_batch (purchase_item_id),
+    FOREIGN KEY (return_id) REFERENCES sale_returns(id) ON DELETE CASCADE,
+    FOREIGN KEY (sale_item_id) REFERENCES sale_items(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id) REFERENCES products(id),
+    FOREIGN KEY (unit_id) REFERENCES units(id)
+);
+
+CREATE TABLE IF NOT EXISTS share_links (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    document_type VARCHAR(32) NOT NULL,
+    document_id BIGINT NOT NULL,
+    url TEXT NOT NULL,
+    remote_path TEXT NOT NULL,
+    expires_at DATETIME NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'active',
+    created_by BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_share_links_document (document_type, document_id),
+    INDEX idx_share_links_status (status, expires_at)
+);
+
+CREATE TABLE IF NOT EXISTS cash_drawer_assignments (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    user_id BIGINT NOT NULL,
+    account_id BIGINT NOT NULL,
+    drop_account_id BIGINT NOT NULL,
+    assigned_by BIGINT,
+    assigned_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    released_at DATETIME,
+    INDEX idx_cash_drawer_assignments_user (user_id, released_at),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id),
+    FOREIGN KEY (drop_account_id) REFERENCES accounts(id)
+);
+
+CREATE TABLE IF NOT EXISTS cash_drops (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    assignment_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    from_account_id BIGINT NOT NULL,
+    to_account_id BIGINT NOT NULL,
+    currency_id BIGINT NOT NULL,
+    amount DECIMAL(18,4) NOT NULL,
+    rate DOUBLE NOT NULL DEFAULT 1,
+    date VARCHAR(32) NOT NULL,
+    notes TEXT,
+    out_transaction_id BIGINT,
+    in_transaction_id BIGINT,
+    journal_entry_id BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_cash_drops_assignment (assignment_id),
+    FOREIGN KEY (assignment_id) REFERENCES cash_drawer_assignments(id) ON DELETE CASCADE,
+    FOREIGN KEY (currency_id) REFERENCES currencies(id)
+);
+
+CREATE TABLE IF NOT EXISTS cash_drawer_counts (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    assignment_id BIGINT NOT NULL,
+    currency_id BIGINT NOT NULL,
+    counted DECIMAL(18,4) NOT NULL,
+    expected DECIMAL(18,4) NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (assignment_id) REFERENCES cash_drawer_assignments(id) ON DELETE CASCADE,
+    FOREIGN KEY (currency_id) REFERENCES currencies(id)
+);
+
+-- Warehouses: stock locations; batches and sale lines without a warehouse belong to the default one
+CREATE TABLE IF NOT EXISTS warehouses (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    code VARCHAR(32),
+    address TEXT,
+    is_default TINYINT NOT NULL DEFAULT 0,
+    is_active TINYINT NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Stock transfers: batch quantities moved from one warehouse to another
+CREATE TABLE IF NOT EXISTS stock_transfers (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    date VARCHAR(32) NOT NULL,
+    from_warehouse_id BIGINT NOT NULL,
+    to_warehouse_id BIGINT NOT NULL,
+    notes TEXT,
+    created_by BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_stock_transfers_from (from_warehouse_id),
+    INDEX idx_stock_transfers_to (to_warehouse_id),
+    FOREIGN KEY (from_warehouse_id) REFERENCES warehouses(id),
+    FOREIGN KEY (to_warehouse_id) REFERENCES warehouses(id)
+);
+
+CREATE TABLE IF NOT EXISTS stock_transfer_items (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    transfer_id BIGINT NOT NULL,
+    purchase_item_id BIGINT NOT NULL,
+    product_id BIGINT NOT NULL,
+    unit_id BIGINT NOT NULL,
+    quantity DOUBLE NOT NULL,
+    INDEX idx_stock_transfer_items_batch (purchase_item_id),
+    FOREIGN KEY (transfer_id) REFERENCES stock_transfers(id) ON DELETE CASCADE,
+    FOREIGN KEY (purchase_item_id) REFERENCES purchase_items(id),
+    FOREIGN KEY (product_id) REFERENCES products(id),
+    FOREIGN KEY (unit_id) REFERENCES units(id)
+);
+
+-- Stock snapshots: each product's closing stock per day, for the inventory valuation history
+CREATE TABLE IF NOT EXISTS stock_snapshots (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    snapshot_date DATE NOT NULL,
+    product_id BIGINT NOT NULL,
+    quantity DOUBLE NOT NULL,
+    value DECIMAL(18,4) NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_stock_snapshots_day_product (snapshot_date, product_id),
+    INDEX idx_stock_snapshots_product (product_id),
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
+);
+
+-- Invoice number sequences: the next number per year (year 0 when numbering never restarts)
+CREATE TABLE IF NOT EXISTS invoice_sequences (
+    year INT PRIMARY KEY,
+    next_value BIGINT NOT NULL DEFAULT 1,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Online storefront: products pushed to it and paid orders pulled from it
+CREATE TABLE IF NOT EXISTS storefront_products (
+    product_id BIGINT PRIMARY KEY,
+    remote_id BIGINT,
+    enabled TINYINT NOT NULL DEFAULT 1,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    message TEXT,
+    last_price DECIMAL(18,6),
+    last_stock BIGINT,
+    synced_at DATETIME,
+    UNIQUE KEY uq_storefront_products_remote (remote_id),
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS storefront_orders (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    remote_id BIGINT NOT NULL UNIQUE,
+    number VARCHAR(64) NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'draft',
+    customer_name VARCHAR(255) NOT NULL DEFAULT '',
+    phone VARCHAR(64),
+    address TEXT,
+    total DECIMAL(18,4) NOT NULL DEFAULT 0,
+    ordered_at VARCHAR(32) NOT NULL,
+    message TEXT,
+    sale_id BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_storefront_orders_status (status)
+);
+
+CREATE TABLE IF NOT EXISTS storefront_order_items (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    order_id BIGINT NOT NULL,
+    remote_product_id BIGINT NOT NULL,
+    product_id BIGINT,
+    name VARCHAR(255) NOT NULL DEFAULT '',
+    quantity DOUBLE NOT NULL,
+    per_price DECIMAL(18,6) NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES storefront_orders(id) ON DELETE CASCADE
+);
+
+-- Customer tags (also used as groups) and SMS/WhatsApp campaigns with per-recipient delivery status
+CREATE TABLE IF NOT EXISTS customer_tags (
+    customer_id BIGINT NOT NULL,
+    tag VARCHAR(64) NOT NULL,
+    PRIMARY KEY (customer_id, tag),
+    INDEX idx_customer_tags_tag (tag),
+    FOREIGN KEY (customer_id) REFERENCES customers(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS campaigns (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(255) NOT NULL,
+    channel VARCHAR(16) NOT NULL,
+    message TEXT NOT NULL,
+    segment TEXT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'draft',
+    scheduled_at DATETIME,
+    started_at DATETIME,
+    finished_at DATETIME,
+    created_by BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_campaigns_status (status, scheduled_at)
+);
+
+CREATE TABLE IF NOT EXISTS campaign_recipients (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    campaign_id BIGINT NOT NULL,
+    customer_id BIGINT NOT NULL,
+    phone VARCHAR(32),
+    message TEXT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    provider_message_id VARCHAR(64),
+    error TEXT,
+    sent_at DATETIME,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_campaign_recipients_status (campaign_id, status),
+    FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE CASCADE
+);
+
+-- Period sign-offs: hashes of a period's journal and account transaction rows when the accountant finalized it
+CREATE TABLE IF NOT EXISTS finalized_periods (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    period VARCHAR(32) NOT NULL,
+    from_date VARCHAR(10) NOT NULL,
+    to_date VARCHAR(10) NOT NULL,
+    hash CHAR(64) NOT NULL,
+    table_hashes TEXT NOT NULL,
+    reviewer VARCHAR(255),
+    notes TEXT,
+    finalized_by BIGINT,
+    finalized_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    verified_at DATETIME,
+    verified_ok TINYINT,
+    reopened_at DATETIME,
+    reopened_by BIGINT,
+    reopen_reason TEXT,
+    INDEX idx_finalized_periods_range (from_date, to_date)
+);
+
+-- Settings that differ per warehouse, terminal or user (most specific wins over global)
+CREATE TABLE IF NOT EXISTS scoped_settings (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    setting_key VARCHAR(64) NOT NULL,
+    scope VARCHAR(16) NOT NULL,
+    scope_id VARCHAR(191) NOT NULL DEFAULT '',
+    value TEXT NOT NULL,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_scoped_setting (setting_key, scope, scope_id)
+);
+
 -- Default currencies (افغانی as base)
 INSERT IGNORE INTO currencies (name, base, rate) VALUES
     ('افغانی', 1, 1.0),
@@ -447,3 +1412,5 @@ INSERT IGNORE INTO units (name, group_id, ratio, is_base)
 SELECT 'میلی‌لیتر', id, 0.001, 0 FROM unit_groups WHERE name = 'حجم' LIMIT 1;
 
 INSERT IGNORE INTO company_settings (id, name, logo, phone, address, font) VALUES (1, 'شرکت', NULL, NULL, NULL, NULL);
+
+INSERT IGNORE INTO warehouses (id, name, is_default) VALUES (1, 'انبار اصلی', 1);
@@ -113,3 +113,23 @@ export async function updateCustomer(
 export async function deleteCustomer(id: number): Promise<string> {
   return await invoke<string>("delete_customer", { id });
 }
+
+/** Everything stored about one customer, as returned by export_customer_data. */
+export type CustomerDataPackage = {
+  exported_at: string;
+  customer: Record<string, unknown>;
+  sales: Record<string, unknown>[];
+} & Record<string, unknown>;
+
+/**
+ * Export everything stored about a customer (data-subject request)
+ * @param id Customer ID
+ * @param outputPath Optional path to also write the JSON package to
+ * @returns Promise with the export package; pass it to exportCustomerDataToPDF for the PDF version
+ */
+export async function exportCustomerData(id: number, outputPath?: string | null): Promise<CustomerDataPackage> {
+  return await invoke<CustomerDataPackage>("export_customer_data", {
+    customerId: id,
+    outputPath: outputPath || null,
+  });
+}
@@ -7,6 +7,12 @@ export interface EnvConfig {
   user: string;
   password: string;
   database: string;
+  /** Terminal settings, stored in this computer's .env */
+  terminal_name?: string;
+  auto_lock_minutes?: number | null;
+  backup_dir?: string | null;
+  receipt_printer?: string | null;
+  warehouse_id?: number | null;
 }
 
 /**
@@ -20,6 +26,7 @@ export async function getEnvConfig(): Promise<EnvConfig> {
 /**
  * Save database configuration to .env and reload in the app.
  * After calling this, retry opening the database (e.g. ensureDatabase).
+ * Terminal settings left undefined are unchanged; an empty string (or a negative auto-lock, or warehouse 0) clears them.
  */
 export async function saveEnvConfig(config: {
   host: string;
@@ -27,6 +34,11 @@ export async function saveEnvConfig(config: {
   user: string;
   password: string;
   database: string;
+  terminal_name?: string;
+  auto_lock_minutes?: number;
+  backup_dir?: string;
+  receipt_printer?: string;
+  warehouse_id?: number;
 }): Promise<void> {
   await invoke("save_env_config", {
     host: config.host,
@@ -34,6 +46,11 @@ export async function saveEnvConfig(config: {
     user: config.user,
     password: config.password,
     database: config.database,
+    terminalName: config.terminal_name,
+    autoLockMinutes: config.auto_lock_minutes,
+    backupDir: config.backup_dir,
+    receiptPrinter: config.receipt_printer,
+    warehouseId: config.warehouse_id,
   });
 }
 
@@ -190,6 +190,93 @@ export async function exportReportToPDF(
   }
 }
 
+const CUSTOMER_EXPORT_SECTIONS: { key: string; label: string }[] = [
+  { key: "sales", label: "فروشات" },
+  { key: "sale_items", label: "اقلام فروش" },
+  { key: "sale_payments", label: "پرداخت‌ها" },
+  { key: "sale_returns", label: "برگشت فروش" },
+  { key: "containers", label: "ظروف" },
+  { key: "backorders", label: "سفارش‌های معوق" },
+  { key: "receivable_write_offs", label: "حذف مطالبات" },
+  { key: "collection_contacts", label: "تماس‌های وصول" },
+  { key: "tags", label: "برچسب‌ها" },
+  { key: "campaign_messages", label: "پیام‌های کمپاین" },
+  { key: "storefront_orders", label: "سفارش‌های آنلاین" },
+  { key: "audit_log", label: "سابقه تغییرات" },
+];
+
+function escapeHtml(v: unknown): string {
+  const text = v == null ? "" : typeof v === "object" ? JSON.stringify(v) : String(v);
+  return text.replace(/&/g, "&amp;").replace(/</g, "&lt;").replace(/>/g, "&gt;").replace(/"/g, "&quot;");
+}
+
+function customerExportTable(rows: Record<string, unknown>[]): string {
+  const columns = Array.from(new Set(rows.flatMap((r) => Object.keys(r).filter((k) => !Array.isArray(r[k])))));
+  if (rows.length === 0 || columns.length === 0) return "<p>—</p>";
+  const head = columns.map((c) => `<th>${escapeHtml(c)}</th>`).join("");
+  const body = rows.map((r) => `<tr>${columns.map((c) => `<td>${escapeHtml(r[c])}</td>`).join("")}</tr>`).join("");
+  return `<table><thead><tr>${head}</tr></thead><tbody>${body}</tbody></table>`;
+}
+
+/**
+ * Export a customer data package (from exportCustomerData) to PDF: the customer's fields, then one table per
+ * section, with sale items and payments pulled out of the sales.
+ */
+export async function exportCustomerDataToPDF(pkg: import("./customer").CustomerDataPackage): Promise<void> {
+  const sales = pkg.sales ?? [];
+  const sections: Record<string, Record<string, unknown>[]> = {
+    ...Object.fromEntries(
+      Object.entries(pkg).filter(([, v]) => Array.isArray(v)) as [string, Record<string, unknown>[]][]
+    ),
+    sale_items: sales.flatMap((s) => (s.items as Record<string, unknown>[]) ?? []),
+    sale_payments: sales.flatMap((s) => (s.payments as Record<string, unknown>[]) ?? []),
+  };
+  const customerRows = Object.entries(pkg.customer)
+    .map(([k, v]) => `<tr><th>${escapeHtml(k)}</th><td>${escapeHtml(v)}</td></tr>`)
+    .join("");
+
+  const element = document.createElement("div");
+  element.dir = "rtl";
+  element.style.cssText = "position:absolute;left:-9999px;top:0;width:210mm;padding:12mm;background:#fff;color:#222;font-size:9pt;";
+  element.innerHTML = `
+    <style>
+      table { width: 100%; border-collapse: collapse; margin-bottom: 10px; }
+      th, td { border: 1px solid #ccc; padding: 3px 5px; text-align: right; word-break: break-all; }
+      th { background: #f5f5f5; }
+      h2 { font-size: 16pt; margin: 0 0 6px 0; } h3 { font-size: 12pt; margin: 12px 0 6px 0; }
+    </style>
+    <h2>اطلاعات مشتری</h2>
+    <p>${escapeHtml(pkg.exported_at)}</p>
+    <table><tbody>${customerRows}</tbody></table>
+    ${CUSTOMER_EXPORT_SECTIONS.map((s) => `<h3>${s.label}</h3>${customerExportTable(sections[s.key] ?? [])}`).join("")}
+  `;
+  document.body.appendChild(element);
+  let canvas: HTMLCanvasElement;
+  try {
+    canvas = await html2canvas(element, { scale: 2, useCORS: true, logging: false, backgroundColor: "#ffffff" });
+  } finally {
+    document.body.removeChild(element);
+  }
+
+  const pdf = new jsPDF("p", "mm", "a4");
+  const imgWidthMm = 210;
+  const pageHeightMm = 297;
+  const totalHeightMm = (canvas.height / canvas.width) * imgWidthMm;
+  const numPages = Math.ceil(totalHeightMm / pageHeightMm);
+  for (let i = 0; i < numPages; i++) {
+    if (i > 0) pdf.addPage();
+    const ySrc = (i * pageHeightMm / totalHeightMm) * canvas.height;
+    const hSrc = Math.min((pageHeightMm / totalHeightMm) * canvas.height, canvas.height - ySrc);
+    const temp = document.createElement("canvas");
+    temp.width = canvas.width;
+    temp.height = hSrc;
+    temp.getContext("2d")!.drawImage(canvas, 0, ySrc, canvas.width, hSrc, 0, 0, canvas.width, hSrc);
+    pdf.addImage(temp.toDataURL("image/png"), "PNG", 0, 0, imgWidthMm, (hSrc / canvas.width) * imgWidthMm);
+  }
+  const name = sanitizeFilename(String(pkg.customer.full_name ?? pkg.customer.id ?? "customer"));
+  pdf.save(`اطلاعات-مشتری-${name}-${new Date().toISOString().slice(0, 10)}.pdf`);
+}
+
 /**
  * Export report to Excel
  */
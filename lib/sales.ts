@@ -15,6 +15,7 @@ export interface Sale {
     order_discount_value?: number;
     order_discount_amount?: number;
     discount_code_id?: number | null;
+    invoice_number?: string | null;
     created_at: string;
     updated_at: string;
 }
@@ -104,6 +105,8 @@ export interface ProductBatch {
     per_unit?: number | null;
     wholesale_price?: number | null;
     retail_price?: number | null;
+    /** Price a sale starts from at this terminal's price level */
+    sale_price?: number | null;
     amount: number;
     remaining_quantity: number;
 }
@@ -0,0 +1,56 @@
+//! Age buckets for aging reports: bucket bounds are the inclusive upper day limits of every bucket but the last
+//! (e.g. [30, 90, 180] → 0-30, 31-90, 91-180, 180+).
+
+/// Default bucket bounds in days.
+pub const DEFAULT_BOUNDS: [i64; 3] = [30, 90, 180];
+/// Default bounds of the receivables and payables aging: 0-30, 31-60, 61-90, 90+.
+pub const ACCOUNT_BOUNDS: [i64; 3] = [30, 60, 90];
+
+/// Sorted, de-duplicated positive bounds; the defaults when none are usable.
+pub fn normalize_bounds(bounds: Option<&[i64]>) -> Vec<i64> {
+    let mut b: Vec<i64> = bounds.unwrap_or(&[]).iter().copied().filter(|d| *d > 0).collect();
+    b.sort_unstable();
+    b.dedup();
+    if b.is_empty() {
+        DEFAULT_BOUNDS.to_vec()
+    } else {
+        b
+    }
+}
+
+/// Labels for the buckets of `bounds` (one more than the number of bounds).
+pub fn labels(bounds: &[i64]) -> Vec<String> {
+    let mut out = Vec::with_capacity(bounds.len() + 1);
+    let mut from = 0;
+    for b in bounds {
+        out.push(format!("{}-{}", from, b));
+        from = b + 1;
+    }
+    out.push(format!("{}+", bounds.last().copied().unwrap_or(0)));
+    out
+}
+
+/// Index of the bucket an age in days falls into (negative ages count as 0).
+pub fn bucket_index(days: i64, bounds: &[i64]) -> usize {
+    let days = days.max(0);
+    bounds.iter().position(|b| days <= *b).unwrap_or(bounds.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buckets() {
+        let bounds = normalize_bounds(None);
+        assert_eq!(labels(&bounds), vec!["0-30", "31-90", "91-180", "180+"]);
+        assert_eq!(bucket_index(0, &bounds), 0);
+        assert_eq!(bucket_index(30, &bounds), 0);
+        assert_eq!(bucket_index(31, &bounds), 1);
+        assert_eq!(bucket_index(180, &bounds), 2);
+        assert_eq!(bucket_index(181, &bounds), 3);
+        assert_eq!(normalize_bounds(Some(&[60, 0, 15, 60])), vec![15, 60]);
+        assert_eq!(labels(&[15, 60]), vec!["0-15", "16-60", "60+"]);
+        assert_eq!(labels(&ACCOUNT_BOUNDS), vec!["0-30", "31-60", "61-90", "90+"]);
+    }
+}
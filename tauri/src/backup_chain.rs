@@ -0,0 +1,134 @@
+//! Progressive backups: a chain starts with a full mysqldump that records the binary log position it was taken
+//! at, and each incremental backup saves the binary log events from the end of the previous link to the current
+//! position. Restoring a chain loads the full dump and replays the increments in order.
+
+use serde::{Deserialize, Serialize};
+
+/// A point in the server's binary log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BinlogPosition {
+    pub file: String,
+    pub position: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupKind {
+    Full,
+    Incremental,
+}
+
+/// One link of a chain; `file` is relative to the chain folder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainEntry {
+    pub kind: BackupKind,
+    pub file: String,
+    pub created_unix: u64,
+    pub created_at: String,
+    pub bytes: u64,
+    /// Binary log position the link starts at (None for a full dump).
+    pub start: Option<BinlogPosition>,
+    /// Position the next increment continues from; None when the server has no binary log, which ends the chain.
+    pub end: Option<BinlogPosition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupChain {
+    pub id: String,
+    pub entries: Vec<ChainEntry>,
+}
+
+impl BackupChain {
+    pub fn created_unix(&self) -> u64 {
+        self.entries.first().map(|e| e.created_unix).unwrap_or(0)
+    }
+
+    pub fn increments(&self) -> usize {
+        self.entries.iter().filter(|e| e.kind == BackupKind::Incremental).count()
+    }
+}
+
+/// Binary log coordinates written by mysqldump --source-data=2 (or the older --master-data=2) as a comment:
+/// "-- CHANGE REPLICATION SOURCE TO SOURCE_LOG_FILE='binlog.000012', SOURCE_LOG_POS=157;".
+pub fn parse_dump_position(line: &str) -> Option<BinlogPosition> {
+    let upper = line.to_uppercase();
+    if !upper.contains("CHANGE MASTER TO") && !upper.contains("CHANGE REPLICATION SOURCE TO") {
+        return None;
+    }
+    let value = |keys: [&str; 2]| {
+        keys.iter().find_map(|key| {
+            let start = upper.find(key)? + key.len();
+            let rest = line[start..].trim_start().strip_prefix('=')?.trim_start();
+            let end = rest.find([',', ';']).unwrap_or(rest.len());
+            Some(rest[..end].trim().trim_matches('\'').to_string())
+        })
+    };
+    let file = value(["SOURCE_LOG_FILE", "MASTER_LOG_FILE"])?;
+    let position = value(["SOURCE_LOG_POS", "MASTER_LOG_POS"])?.parse().ok()?;
+    Some(BinlogPosition { file, position })
+}
+
+/// Binary log files holding the events after `start`, given the server's logs in order with the current (just
+/// rotated, still empty) one last. None when `start`'s file has been purged, so the chain cannot continue.
+pub fn files_since(logs: &[String], start: &BinlogPosition) -> Option<Vec<String>> {
+    let from = logs.iter().position(|f| *f == start.file)?;
+    Some(logs[from..logs.len().saturating_sub(1).max(from)].to_vec())
+}
+
+/// Whether the next backup must start a new chain: there is none, it cannot be continued, its full dump is
+/// `full_every_days` old or it already has `max_increments` increments.
+pub fn needs_full(chain: Option<&BackupChain>, now_unix: u64, full_every_days: u32, max_increments: usize) -> bool {
+    let Some(chain) = chain else { return true };
+    let continuable = chain.entries.last().is_some_and(|e| e.end.is_some());
+    let age_days = now_unix.saturating_sub(chain.created_unix()) / 86_400;
+    !continuable || age_days >= full_every_days as u64 || chain.increments() >= max_increments
+}
+
+/// Ids of the chains to delete so only the newest `keep` remain (at least one is always kept).
+pub fn chains_to_prune(chains: &[BackupChain], keep: usize) -> Vec<String> {
+    let mut ordered: Vec<&BackupChain> = chains.iter().collect();
+    ordered.sort_by_key(|c| std::cmp::Reverse(c.created_unix()));
+    ordered.into_iter().skip(keep.max(1)).map(|c| c.id.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_planning() {
+        let pos = |file: &str, position| BinlogPosition { file: file.to_string(), position };
+        assert_eq!(
+            parse_dump_position("-- CHANGE REPLICATION SOURCE TO SOURCE_LOG_FILE='binlog.000012', SOURCE_LOG_POS=157;"),
+            Some(pos("binlog.000012", 157))
+        );
+        assert_eq!(parse_dump_position("-- CHANGE MASTER TO MASTER_LOG_FILE='mysql-bin.000003', MASTER_LOG_POS=4;"), Some(pos("mysql-bin.000003", 4)));
+        assert_eq!(parse_dump_position("-- Dump completed"), None);
+
+        let logs: Vec<String> = ["b.1", "b.2", "b.3", "b.4"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(files_since(&logs, &pos("b.2", 900)), Some(vec!["b.2".to_string(), "b.3".to_string()]));
+        assert_eq!(files_since(&logs, &pos("b.0", 4)), None);
+
+        let entry = |kind, created_unix, end: Option<BinlogPosition>| ChainEntry {
+            kind,
+            file: String::new(),
+            created_unix,
+            created_at: String::new(),
+            bytes: 0,
+            start: None,
+            end,
+        };
+        let day = 86_400;
+        let chain = BackupChain {
+            id: "a".to_string(),
+            entries: vec![entry(BackupKind::Full, 0, Some(pos("b.1", 4))), entry(BackupKind::Incremental, day, Some(pos("b.2", 4)))],
+        };
+        assert!(needs_full(None, 0, 7, 30));
+        assert!(!needs_full(Some(&chain), 3 * day, 7, 30));
+        assert!(needs_full(Some(&chain), 7 * day, 7, 30));
+        assert!(needs_full(Some(&chain), 3 * day, 7, 1));
+        let ended = BackupChain { id: "b".to_string(), entries: vec![entry(BackupKind::Full, 10 * day, None)] };
+        assert!(needs_full(Some(&ended), 10 * day, 7, 30));
+        assert_eq!(chains_to_prune(&[chain, ended], 1), vec!["a".to_string()]);
+    }
+}
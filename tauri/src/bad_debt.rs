@@ -0,0 +1,57 @@
+//! Bad debts: spreading a write-off over a customer's unpaid sales and estimating doubtful debts by age.
+
+/// bad_debt: a balance given up as uncollectible; returned_cheque: a balance left by a cheque that bounced and will
+/// not be made good.
+pub const WRITE_OFF_KINDS: [&str; 2] = ["bad_debt", "returned_cheque"];
+
+/// Default share of each aging bucket expected to go unpaid, in percent; buckets past the last get the last rate.
+pub const DEFAULT_PROVISION_RATES: [f64; 4] = [1.0, 5.0, 25.0, 50.0];
+
+/// Split `amount` over unpaid sales (sale_id, unpaid), oldest first as given, never more than a sale's unpaid
+/// amount. Whatever the sales cannot take is left out.
+pub fn allocate(amount: f64, unpaid: &[(i64, f64)]) -> Vec<(i64, f64)> {
+    let mut left = amount;
+    let mut out = Vec::new();
+    for (sale_id, open) in unpaid {
+        if left < 0.005 {
+            break;
+        }
+        let take = ((left.min(*open)) * 100.0).round() / 100.0;
+        if take > 0.0 {
+            out.push((*sale_id, take));
+            left -= take;
+        }
+    }
+    out
+}
+
+/// Provision rate per bucket (`buckets` of them): the given rates clamped to 0-100, or the defaults, padded with
+/// the last rate.
+pub fn provision_rates(rates: Option<&[f64]>, buckets: usize) -> Vec<f64> {
+    let given: Vec<f64> = rates.unwrap_or(&[]).iter().map(|r| r.clamp(0.0, 100.0)).collect();
+    let base = if given.is_empty() { DEFAULT_PROVISION_RATES.to_vec() } else { given };
+    let last = base.last().copied().unwrap_or(0.0);
+    (0..buckets).map(|i| base.get(i).copied().unwrap_or(last)).collect()
+}
+
+/// Doubtful part of `outstanding` at `rate_percent`.
+pub fn provision(outstanding: f64, rate_percent: f64) -> f64 {
+    (outstanding * rate_percent / 100.0 * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocation_and_provision() {
+        let unpaid = [(1, 300.0), (2, 500.0), (3, 200.0)];
+        assert_eq!(allocate(650.0, &unpaid), vec![(1, 300.0), (2, 350.0)]);
+        assert_eq!(allocate(2000.0, &unpaid), vec![(1, 300.0), (2, 500.0), (3, 200.0)]);
+        assert_eq!(allocate(0.0, &unpaid), vec![]);
+        assert_eq!(provision_rates(None, 4), vec![1.0, 5.0, 25.0, 50.0]);
+        assert_eq!(provision_rates(None, 5), vec![1.0, 5.0, 25.0, 50.0, 50.0]);
+        assert_eq!(provision_rates(Some(&[10.0, 150.0]), 3), vec![10.0, 100.0, 100.0]);
+        assert_eq!(provision(1234.0, 5.0), 61.7);
+    }
+}
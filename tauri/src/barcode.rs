@@ -0,0 +1,232 @@
+//! Barcodes for product labels: EAN-13 for 13-digit codes with a valid check digit, Code 128 for anything else
+//! (set C for even-length digit strings, set B otherwise). A barcode is its row of modules, drawn as SVG for label
+//! sheets or as a PNG image.
+
+use serde::{Deserialize, Serialize};
+
+/// Blank modules on each side; EAN-13 needs 11 on the left, Code 128 at least 10.
+pub const QUIET_ZONE: usize = 11;
+/// Longest code printed as Code 128; longer codes do not fit a label.
+pub const MAX_CODE128_LEN: usize = 40;
+
+/// Widths of bar, space, bar, ... for Code 128 values 0 to 105 (103-105 are the start codes A, B and C).
+const CODE128: [&str; 106] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212", "221213", "221312",
+    "231212", "112232", "122132", "122231", "113222", "123122", "123221", "223211", "221132", "221231", "213212",
+    "223112", "312131", "311222", "321122", "321221", "312212", "322112", "322211", "212123", "212321", "232121",
+    "111323", "131123", "131321", "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331",
+    "132131", "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131", "311123",
+    "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111", "111224", "111422", "121124",
+    "121421", "141122", "141221", "112214", "112412", "122114", "122411", "142112", "142211", "241211", "221114",
+    "413111", "241112", "134111", "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112",
+    "421211", "212141", "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232",
+];
+const CODE128_STOP: &str = "2331112";
+const START_B: usize = 104;
+const START_C: usize = 105;
+
+/// EAN-13 L-code of each digit; R-codes are its complement and G-codes the reversed R-code.
+const EAN_L: [&str; 10] = ["0001101", "0011001", "0010011", "0111101", "0100011", "0110001", "0101111", "0111011", "0110111", "0001011"];
+/// Codes of digits 2-7 (L or G), by the first digit.
+const EAN_PARITY: [&str; 10] = ["LLLLLL", "LLGLGG", "LLGGLG", "LLGGGL", "LGLLGG", "LGGLLG", "LGGGLL", "LGLGLG", "LGLGGL", "LGGLGL"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Symbology {
+    Ean13,
+    Code128,
+}
+
+/// EAN-13 check digit of the first 12 digits.
+pub fn ean13_check_digit(digits: &str) -> Option<u32> {
+    let digits: Vec<u32> = digits.chars().map(|c| c.to_digit(10)).collect::<Option<_>>()?;
+    if digits.len() != 12 {
+        return None;
+    }
+    let sum: u32 = digits.iter().enumerate().map(|(i, d)| if i % 2 == 0 { *d } else { d * 3 }).sum();
+    Some((10 - sum % 10) % 10)
+}
+
+fn push_bits(modules: &mut Vec<bool>, bits: &str) {
+    modules.extend(bits.chars().map(|c| c == '1'));
+}
+
+fn push_widths(modules: &mut Vec<bool>, widths: &str) {
+    for (i, w) in widths.chars().enumerate() {
+        let width = w.to_digit(10).unwrap_or(1) as usize;
+        modules.resize(modules.len() + width, i % 2 == 0);
+    }
+}
+
+fn ean13(code: &str) -> Vec<bool> {
+    let digits: Vec<usize> = code.chars().filter_map(|c| c.to_digit(10)).map(|d| d as usize).collect();
+    let r_code = |d: usize| EAN_L[d].chars().map(|c| if c == '1' { '0' } else { '1' }).collect::<String>();
+    let mut modules = Vec::with_capacity(95);
+    push_bits(&mut modules, "101");
+    for (i, parity) in EAN_PARITY[digits[0]].chars().enumerate() {
+        let d = digits[i + 1];
+        let bits = if parity == 'G' { r_code(d).chars().rev().collect() } else { EAN_L[d].to_string() };
+        push_bits(&mut modules, &bits);
+    }
+    push_bits(&mut modules, "01010");
+    for d in &digits[7..] {
+        push_bits(&mut modules, &r_code(*d));
+    }
+    push_bits(&mut modules, "101");
+    modules
+}
+
+fn code128(code: &str) -> Result<Vec<bool>, String> {
+    if code.chars().count() > MAX_CODE128_LEN {
+        return Err(format!("Barcode {} is longer than {} characters", code, MAX_CODE128_LEN));
+    }
+    let values: Vec<usize> = if code.len() >= 4 && code.len() % 2 == 0 && code.chars().all(|c| c.is_ascii_digit()) {
+        let pairs = code.as_bytes().chunks(2).map(|p| ((p[0] - b'0') * 10 + (p[1] - b'0')) as usize);
+        std::iter::once(START_C).chain(pairs).collect()
+    } else {
+        if let Some(c) = code.chars().find(|c| !(' '..='~').contains(c)) {
+            return Err(format!("Barcode {} has a character Code 128 cannot encode: {:?}", code, c));
+        }
+        std::iter::once(START_B).chain(code.bytes().map(|b| (b - b' ') as usize)).collect()
+    };
+    let check = values.iter().enumerate().map(|(i, v)| v * i.max(1)).sum::<usize>() % 103;
+    let mut modules = Vec::with_capacity(11 * (values.len() + 1) + 13);
+    for v in values.iter().chain(std::iter::once(&check)) {
+        push_widths(&mut modules, CODE128[*v]);
+    }
+    push_widths(&mut modules, CODE128_STOP);
+    Ok(modules)
+}
+
+/// The barcode of a product code, trimmed: EAN-13 when it is a valid one, else Code 128.
+pub fn encode(code: &str) -> Result<(Symbology, Vec<bool>), String> {
+    let code = code.trim();
+    if code.is_empty() {
+        return Err("Barcode is empty".to_string());
+    }
+    let valid_ean = code.len() == 13 && ean13_check_digit(&code[..12]).is_some_and(|d| code[12..].parse::<u32>() == Ok(d));
+    if valid_ean {
+        Ok((Symbology::Ean13, ean13(code)))
+    } else {
+        code128(code).map(|m| (Symbology::Code128, m))
+    }
+}
+
+/// The bars as an SVG that stretches to its box, with the quiet zones.
+pub fn svg(modules: &[bool]) -> String {
+    let mut path = String::new();
+    let mut x = 0;
+    while x < modules.len() {
+        if modules[x] {
+            let width = modules[x..].iter().take_while(|m| **m).count();
+            path.push_str(&format!("M{},0h{}v1h-{}z", x + QUIET_ZONE, width, width));
+            x += width;
+        } else {
+            x += 1;
+        }
+    }
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} 1" preserveAspectRatio="none" shape-rendering="crispEdges" width="100%" height="100%"><path d="{}" fill="black"/></svg>"#,
+        modules.len() + 2 * QUIET_ZONE,
+        path
+    )
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for b in bytes {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// zlib stream of `data` in stored (uncompressed) blocks; barcode images are small.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = if data.is_empty() { vec![&[]] } else { data.chunks(65535).collect() };
+    for (i, block) in blocks.iter().enumerate() {
+        out.push(u8::from(i + 1 == blocks.len()));
+        let len = block.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), byte| {
+        let a = (a + *byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    out.extend_from_slice(&((b << 16) | a).to_be_bytes());
+    out
+}
+
+/// The bars as a grayscale PNG, `module_px` pixels per module and `height_px` high, with the quiet zones.
+pub fn png(modules: &[bool], module_px: u32, height_px: u32) -> Vec<u8> {
+    let module_px = module_px.max(1) as usize;
+    let quiet = vec![0xFFu8; QUIET_ZONE * module_px];
+    let mut row = vec![0u8];
+    row.extend_from_slice(&quiet);
+    for m in modules {
+        row.resize(row.len() + module_px, if *m { 0x00 } else { 0xFF });
+    }
+    row.extend_from_slice(&quiet);
+    let width = (row.len() - 1) as u32;
+    let height = height_px.max(1);
+    let pixels = row.repeat(height as usize);
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[8, 0, 0, 0, 0]);
+    let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+    png_chunk(&mut out, b"IHDR", &header);
+    png_chunk(&mut out, b"IDAT", &zlib_stored(&pixels));
+    png_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_barcodes() {
+        assert!(CODE128.iter().all(|p| p.chars().filter_map(|c| c.to_digit(10)).sum::<u32>() == 11));
+        assert_eq!(ean13_check_digit("400638133393"), Some(1));
+        assert_eq!(ean13_check_digit("40063813339"), None);
+
+        let (symbology, modules) = encode(" 4006381333931 ").unwrap();
+        assert_eq!(symbology, Symbology::Ean13);
+        assert_eq!(modules.len(), 95);
+        let bits: String = modules[..10].iter().map(|m| if *m { '1' } else { '0' }).collect();
+        // Guard, then 0 as an L-code (first digit 4 puts digit 2 in L)
+        assert_eq!(bits, "1010001101");
+
+        // A bad check digit is not EAN-13
+        assert_eq!(encode("4006381333932").unwrap().0, Symbology::Code128);
+        let (symbology, modules) = encode("TEA-500").unwrap();
+        assert_eq!(symbology, Symbology::Code128);
+        assert_eq!(modules.len(), 11 * (7 + 2) + 13);
+        assert_eq!(encode("123456").unwrap().1.len(), 11 * (3 + 2) + 13);
+        assert!(encode("چای").is_err());
+        assert!(encode("  ").is_err());
+
+        assert!(svg(&modules).starts_with("<svg") && svg(&modules).contains(&format!("M{},0h2v1h-2z", QUIET_ZONE)));
+        let image = png(&modules, 2, 40);
+        assert_eq!(&image[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(u32::from_be_bytes([image[16], image[17], image[18], image[19]]), ((modules.len() + 2 * QUIET_ZONE) * 2) as u32);
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+    }
+}
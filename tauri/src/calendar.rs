@@ -0,0 +1,115 @@
+//! Business calendar: the day weeks start on, the weekend days and holidays, used to bucket reports by week and to
+//! move due dates off days off. Weekdays are numbered 0 (Sunday) to 6 (Saturday), like MySQL's DAYOFWEEK() - 1.
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+pub const WEEKDAY_NAMES: [&str; 7] = ["sunday", "monday", "tuesday", "wednesday", "thursday", "friday", "saturday"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calendar {
+    pub week_start: u32,
+    pub weekend: Vec<u32>,
+    pub holidays: Vec<NaiveDate>,
+    /// Holidays on the same (month, day) every year.
+    pub annual_holidays: Vec<(u32, u32)>,
+}
+
+impl Default for Calendar {
+    /// Afghan working week: Saturday to Thursday, Friday off.
+    fn default() -> Self {
+        Calendar { week_start: 6, weekend: vec![5], holidays: Vec::new(), annual_holidays: Vec::new() }
+    }
+}
+
+/// Weekday number of a name ("saturday"), its first three letters ("sat") or a number 0-6.
+pub fn parse_weekday(name: &str) -> Option<u32> {
+    let name = name.trim().to_lowercase();
+    if let Ok(n) = name.parse::<u32>() {
+        return (n < 7).then_some(n);
+    }
+    if name.len() < 3 {
+        return None;
+    }
+    WEEKDAY_NAMES.iter().position(|w| w.starts_with(&name)).map(|i| i as u32)
+}
+
+pub fn weekday(date: NaiveDate) -> u32 {
+    date.weekday().num_days_from_sunday()
+}
+
+impl Calendar {
+    /// First day of the week `date` falls in.
+    pub fn week_start_of(&self, date: NaiveDate) -> NaiveDate {
+        let back = (weekday(date) + 7 - self.week_start % 7) % 7;
+        date - Duration::days(back as i64)
+    }
+
+    pub fn is_weekend(&self, date: NaiveDate) -> bool {
+        self.weekend.contains(&weekday(date))
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holidays.contains(&date) || self.annual_holidays.contains(&(date.month(), date.day()))
+    }
+
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !self.is_weekend(date) && !self.is_holiday(date)
+    }
+
+    /// Move a date that falls on a day off: "following" (next business day), "preceding" (previous one) or
+    /// "modified_following" (next, unless that is in the next month, then previous). Any other rule, or a calendar
+    /// without business days, leaves the date as it is.
+    pub fn adjust(&self, date: NaiveDate, rule: &str) -> NaiveDate {
+        let step = |dir: i64| {
+            (1..=366).map(|i| date + Duration::days(i * dir)).find(|d| self.is_business_day(*d))
+        };
+        if self.is_business_day(date) {
+            return date;
+        }
+        let moved = match rule {
+            "following" => step(1),
+            "preceding" => step(-1),
+            "modified_following" => step(1).filter(|d| d.month() == date.month()).or_else(|| step(-1)),
+            _ => None,
+        };
+        moved.unwrap_or(date)
+    }
+
+    /// The seven weekdays in display order, starting with the week start.
+    pub fn week_order(&self) -> Vec<u32> {
+        (0..7).map(|i| (self.week_start + i) % 7).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weeks_and_weekends() {
+        let cal = Calendar::default();
+        let wed = NaiveDate::from_ymd_opt(2024, 3, 6).unwrap();
+        assert_eq!(cal.week_start_of(wed), NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
+        assert_eq!(cal.week_start_of(NaiveDate::from_ymd_opt(2024, 3, 2).unwrap()), NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
+        assert!(cal.is_weekend(NaiveDate::from_ymd_opt(2024, 3, 8).unwrap()));
+        assert!(!cal.is_weekend(wed));
+        assert_eq!(cal.week_order(), vec![6, 0, 1, 2, 3, 4, 5]);
+
+        let monday = Calendar { week_start: 1, weekend: vec![0, 6], ..Default::default() };
+        assert_eq!(monday.week_start_of(wed), NaiveDate::from_ymd_opt(2024, 3, 4).unwrap());
+
+        // Thursday 2024-03-21 is Nowruz and Friday the weekend
+        let cal = Calendar { annual_holidays: vec![(3, 21)], holidays: vec![NaiveDate::from_ymd_opt(2024, 3, 30).unwrap()], ..Default::default() };
+        let nowruz = NaiveDate::from_ymd_opt(2024, 3, 21).unwrap();
+        assert_eq!(cal.adjust(nowruz, "following"), NaiveDate::from_ymd_opt(2024, 3, 23).unwrap());
+        assert_eq!(cal.adjust(nowruz, "preceding"), NaiveDate::from_ymd_opt(2024, 3, 20).unwrap());
+        assert_eq!(cal.adjust(nowruz, "none"), nowruz);
+        // Saturday the 30th is a holiday and Sunday the 31st is a working day
+        assert_eq!(cal.adjust(NaiveDate::from_ymd_opt(2024, 3, 30).unwrap(), "modified_following"), NaiveDate::from_ymd_opt(2024, 3, 31).unwrap());
+        assert_eq!(cal.adjust(NaiveDate::from_ymd_opt(2024, 3, 29).unwrap(), "modified_following"), NaiveDate::from_ymd_opt(2024, 3, 31).unwrap());
+        assert_eq!(parse_weekday("Sat"), Some(6));
+        assert_eq!(parse_weekday("friday"), Some(5));
+        assert_eq!(parse_weekday("7"), None);
+        assert_eq!(parse_weekday("s"), None);
+    }
+}
@@ -0,0 +1,81 @@
+//! Command-line maintenance mode: with one of these options the binary does the task against the configured
+//! database and exits without opening a window.
+
+pub const USAGE: &str = "Maintenance options (the app opens normally without them):
+  --backup [--output FILE]          dump the database with mysqldump
+  --restore FILE                    restore a dump (the users table is kept)
+  --migrate                         create missing tables and columns
+  --verify-integrity                check tables, journal balance and batch stock
+  --export TABLE [--output FILE]    write a table as CSV (stdout without --output)
+  --help                            show this help";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    Backup { output: Option<String> },
+    Restore { file: String },
+    Migrate,
+    VerifyIntegrity,
+    Export { table: String, output: Option<String> },
+    Help,
+}
+
+/// The maintenance task asked for by `args` (without the program name); None when the app should start normally.
+/// Arguments the OS or webview adds are ignored unless a maintenance option is present.
+pub fn parse(args: &[String]) -> Result<Option<CliCommand>, String> {
+    let value_of = |flag: &str| -> Result<Option<String>, String> {
+        match args.iter().position(|a| a == flag) {
+            None => Ok(None),
+            Some(i) => match args.get(i + 1).filter(|v| !v.starts_with("--")) {
+                Some(v) => Ok(Some(v.clone())),
+                None => Err(format!("{} needs a value", flag)),
+            },
+        }
+    };
+    let has = |flag: &str| args.iter().any(|a| a == flag);
+
+    let mut commands = Vec::new();
+    if has("--help") || has("-h") {
+        commands.push(CliCommand::Help);
+    }
+    if has("--backup") {
+        commands.push(CliCommand::Backup { output: value_of("--output")? });
+    }
+    if let Some(file) = value_of("--restore")? {
+        commands.push(CliCommand::Restore { file });
+    }
+    if has("--migrate") {
+        commands.push(CliCommand::Migrate);
+    }
+    if has("--verify-integrity") {
+        commands.push(CliCommand::VerifyIntegrity);
+    }
+    if let Some(table) = value_of("--export")? {
+        commands.push(CliCommand::Export { table, output: value_of("--output")? });
+    }
+    match commands.len() {
+        0 if has("--output") => Err("--output is only used with --backup or --export".to_string()),
+        0 | 1 => Ok(commands.pop()),
+        _ => Err("Give one maintenance option at a time".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+        assert_eq!(parse(&args("")), Ok(None));
+        assert_eq!(parse(&args("--some-webview-flag")), Ok(None));
+        assert_eq!(parse(&args("--backup")), Ok(Some(CliCommand::Backup { output: None })));
+        assert_eq!(
+            parse(&args("--export sales --output /tmp/s.csv")),
+            Ok(Some(CliCommand::Export { table: "sales".to_string(), output: Some("/tmp/s.csv".to_string()) }))
+        );
+        assert_eq!(parse(&args("--restore dump.sql")), Ok(Some(CliCommand::Restore { file: "dump.sql".to_string() })));
+        assert!(parse(&args("--restore")).is_err());
+        assert!(parse(&args("--restore --migrate")).is_err());
+        assert!(parse(&args("--migrate --verify-integrity")).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+//! Clock sanity checks for license expiry: detect the system clock being set back (against a persisted
+//! "last seen" time) and optionally compare with an SNTP server when online.
+
+use chrono::{DateTime, TimeZone, Utc};
+use std::net::UdpSocket;
+use std::time::Duration;
+
+/// How far the clock may go backwards (e.g. NTP correction, DST mistakes) before it counts as tampering.
+pub const ROLLBACK_TOLERANCE_SECS: i64 = 10 * 60;
+/// Maximum accepted difference between the system clock and NTP time.
+pub const NTP_MAX_OFFSET_SECS: i64 = 60 * 60;
+const NTP_SERVER: &str = "pool.ntp.org:123";
+/// Seconds between 1900-01-01 (NTP epoch) and 1970-01-01 (Unix epoch).
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+/// True when `now` is earlier than the last seen time by more than the tolerance.
+pub fn is_rolled_back(now: DateTime<Utc>, last_seen: DateTime<Utc>) -> bool {
+    (last_seen - now).num_seconds() > ROLLBACK_TOLERANCE_SECS
+}
+
+pub fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s.trim()).ok().map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Query an SNTP server (3s timeout). Returns the server's current time.
+pub fn ntp_now() -> Result<DateTime<Utc>, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| format!("NTP socket error: {}", e))?;
+    socket
+        .set_read_timeout(Some(Duration::from_secs(3)))
+        .map_err(|e| format!("NTP socket error: {}", e))?;
+    let mut packet = [0u8; 48];
+    packet[0] = 0x1B; // LI = 0, version 3, mode 3 (client)
+    socket.send_to(&packet, NTP_SERVER).map_err(|e| format!("NTP request failed: {}", e))?;
+    let mut buf = [0u8; 48];
+    let (len, _) = socket.recv_from(&mut buf).map_err(|e| format!("NTP response failed: {}", e))?;
+    if len < 48 {
+        return Err("NTP response too short".to_string());
+    }
+    // Transmit timestamp: seconds at bytes 40..44, fraction at 44..48
+    let secs = u32::from_be_bytes([buf[40], buf[41], buf[42], buf[43]]) as u64;
+    let frac = u32::from_be_bytes([buf[44], buf[45], buf[46], buf[47]]) as u64;
+    if secs < NTP_UNIX_OFFSET {
+        return Err("Invalid NTP timestamp".to_string());
+    }
+    let nanos = ((frac * 1_000_000_000) >> 32) as u32;
+    Utc.timestamp_opt((secs - NTP_UNIX_OFFSET) as i64, nanos)
+        .single()
+        .ok_or_else(|| "Invalid NTP timestamp".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollback_detection() {
+        let last_seen = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        assert!(!is_rolled_back(last_seen + chrono::Duration::days(1), last_seen));
+        assert!(!is_rolled_back(last_seen - chrono::Duration::minutes(5), last_seen));
+        assert!(is_rolled_back(last_seen - chrono::Duration::days(30), last_seen));
+    }
+}
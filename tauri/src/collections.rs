@@ -0,0 +1,39 @@
+//! Collections workflow: ranking overdue customers and judging promises to pay. Dates are "YYYY-MM-DD" strings,
+//! which compare in date order.
+
+pub const CONTACT_METHODS: [&str; 5] = ["call", "visit", "sms", "message", "other"];
+pub const CONTACT_OUTCOMES: [&str; 5] = ["reached", "no_answer", "promised", "refused", "other"];
+
+/// Order of the overdue list: the amount overdue, weighted up by a month's worth for every 30 days it is late.
+pub fn priority(overdue_amount: f64, days_overdue: i64) -> f64 {
+    overdue_amount * (1.0 + days_overdue.max(0) as f64 / 30.0)
+}
+
+/// State of a promise to pay `promised_amount` by `promised_date`, given what the customer has paid since making
+/// it (up to that date): "kept", "broken" once the date has passed, otherwise "open". Within one afghani counts as
+/// paid.
+pub fn promise_status(promised_amount: f64, paid_since: f64, promised_date: &str, today: &str) -> &'static str {
+    if paid_since + 1.0 >= promised_amount {
+        "kept"
+    } else if promised_date < today {
+        "broken"
+    } else {
+        "open"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_and_promises() {
+        assert_eq!(priority(1000.0, 0), 1000.0);
+        assert_eq!(priority(1000.0, 60), 3000.0);
+        assert!(priority(500.0, 90) > priority(1500.0, 0));
+        assert_eq!(promise_status(5000.0, 5000.0, "2024-03-10", "2024-03-20"), "kept");
+        assert_eq!(promise_status(5000.0, 4999.5, "2024-03-10", "2024-03-05"), "kept");
+        assert_eq!(promise_status(5000.0, 2000.0, "2024-03-10", "2024-03-10"), "open");
+        assert_eq!(promise_status(5000.0, 2000.0, "2024-03-10", "2024-03-11"), "broken");
+    }
+}
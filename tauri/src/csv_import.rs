@@ -0,0 +1,240 @@
+//! CSV reading for the template-based importer: RFC 4180 parsing, locale-aware numbers and dates, and turning a
+//! row into named fields through a column mapping.
+
+/// Value type of an import field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldKind {
+    Text,
+    Number,
+    Date,
+}
+
+/// A field an import target accepts.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub kind: FieldKind,
+    pub required: bool,
+}
+
+/// A parsed field value; dates are normalized to YYYY-MM-DD.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Number(f64),
+    Date(String),
+}
+
+/// How a file layout writes its values.
+#[derive(Debug, Clone)]
+pub struct Layout {
+    pub delimiter: char,
+    pub has_header: bool,
+    /// Lines before the header (bank exports often start with account details).
+    pub skip_rows: usize,
+    /// "YYYY-MM-DD", "DD/MM/YYYY", "MM/DD/YYYY" or "DD.MM.YYYY".
+    pub date_format: String,
+    pub decimal_separator: char,
+}
+
+/// Records of a CSV text. Quoted fields may hold delimiters, doubled quotes and line breaks; a leading BOM and
+/// blank lines are dropped.
+pub fn parse(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.trim().is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            _ if c == delimiter => record.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.trim().is_empty()) {
+        records.push(record);
+    }
+    records
+}
+
+/// Persian/Arabic digits to ASCII.
+fn ascii_digits(raw: &str) -> String {
+    raw.chars()
+        .map(|c| match c {
+            '۰'..='۹' => char::from_digit(c as u32 - '۰' as u32, 10).unwrap_or(c),
+            '٠'..='٩' => char::from_digit(c as u32 - '٠' as u32, 10).unwrap_or(c),
+            '٫' => '.',
+            '٬' => ',',
+            _ => c,
+        })
+        .collect()
+}
+
+/// Number written with `decimal_separator`; other separators, spaces and currency signs are ignored, and a
+/// trailing minus or parentheses mean negative ("1.234,50-", "(20)").
+pub fn parse_number(raw: &str, decimal_separator: char) -> Option<f64> {
+    let raw = ascii_digits(raw.trim());
+    let negative = raw.starts_with('-') || raw.ends_with('-') || (raw.starts_with('(') && raw.ends_with(')'));
+    let mut number = String::new();
+    for c in raw.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+        } else if c == decimal_separator {
+            number.push('.');
+        }
+    }
+    if number.is_empty() {
+        return None;
+    }
+    let value: f64 = number.parse().ok()?;
+    Some(if negative { -value } else { value })
+}
+
+/// Date in `format` as YYYY-MM-DD; None when it does not match or is not a calendar date.
+pub fn parse_date(raw: &str, format: &str) -> Option<String> {
+    let raw = ascii_digits(raw.trim());
+    // Time parts after the date are ignored
+    let date = raw.split_whitespace().next()?;
+    let parts: Vec<u32> = date.split(['-', '/', '.']).map(|p| p.parse().ok()).collect::<Option<_>>()?;
+    let [a, b, c] = parts[..] else { return None };
+    let (y, m, d) = match format {
+        "DD/MM/YYYY" | "DD.MM.YYYY" => (c, b, a),
+        "MM/DD/YYYY" => (c, a, b),
+        _ => (a, b, c),
+    };
+    let leap = (y % 4 == 0 && y % 100 != 0) || y % 400 == 0;
+    let days = match m {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    if y < 1000 || d == 0 || d > days {
+        return None;
+    }
+    Some(format!("{:04}-{:02}-{:02}", y, m, d))
+}
+
+/// Position of a mapped column: a header name (case-insensitive) or, for files without a header, its 1-based
+/// number.
+pub fn column_index(headers: &[String], column: &str) -> Option<usize> {
+    let column = column.trim();
+    headers
+        .iter()
+        .position(|h| h.trim().eq_ignore_ascii_case(column))
+        .or_else(|| column.parse::<usize>().ok().filter(|n| *n > 0).map(|n| n - 1))
+}
+
+/// Fields of one record through `mappings` (field name, column). Empty optional fields are left out; every
+/// problem of the row is returned together.
+pub fn map_row(
+    headers: &[String],
+    record: &[String],
+    mappings: &[(String, String)],
+    specs: &[FieldSpec],
+    layout: &Layout,
+) -> Result<Vec<(String, Cell)>, Vec<String>> {
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    for spec in specs {
+        let raw = mappings
+            .iter()
+            .find(|(field, _)| field == spec.name)
+            .and_then(|(_, column)| column_index(headers, column))
+            .and_then(|i| record.get(i))
+            .map(|v| v.trim())
+            .unwrap_or("");
+        if raw.is_empty() {
+            if spec.required {
+                errors.push(format!("{} is required", spec.name));
+            }
+            continue;
+        }
+        let cell = match spec.kind {
+            FieldKind::Text => Some(Cell::Text(raw.to_string())),
+            FieldKind::Number => parse_number(raw, layout.decimal_separator).map(Cell::Number),
+            FieldKind::Date => parse_date(raw, &layout.date_format).map(Cell::Date),
+        };
+        match cell {
+            Some(cell) => values.push((spec.name.to_string(), cell)),
+            None => errors.push(format!("{}: invalid value \"{}\"", spec.name, raw)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(values)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_map() {
+        let text = "\u{feff}Date;Details;Amount\r\n05/03/2024;\"Rent; March\";\"1.250,50-\"\r\n\r\n31/02/2024;Fee;abc\n";
+        let rows = parse(text, ';');
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1], vec!["05/03/2024", "Rent; March", "1.250,50-"]);
+        assert_eq!(parse("a,\"say \"\"hi\"\"\nthere\"", ','), vec![vec!["a", "say \"hi\"\nthere"]]);
+
+        assert_eq!(parse_number("۱٬۲۵۰٫۵", '.'), Some(1250.5));
+        assert_eq!(parse_number("(20)", '.'), Some(-20.0));
+        assert_eq!(parse_date("2024-02-29", "YYYY-MM-DD").as_deref(), Some("2024-02-29"));
+        assert_eq!(parse_date("12/31/2023 10:00", "MM/DD/YYYY").as_deref(), Some("2023-12-31"));
+        assert_eq!(parse_date("29.02.2023", "DD.MM.YYYY"), None);
+
+        let layout = Layout {
+            delimiter: ';',
+            has_header: true,
+            skip_rows: 0,
+            date_format: "DD/MM/YYYY".to_string(),
+            decimal_separator: ',',
+        };
+        let specs = [
+            FieldSpec { name: "date", kind: FieldKind::Date, required: true },
+            FieldSpec { name: "amount", kind: FieldKind::Number, required: true },
+            FieldSpec { name: "description", kind: FieldKind::Text, required: false },
+            FieldSpec { name: "reference", kind: FieldKind::Text, required: false },
+        ];
+        let mappings = vec![
+            ("date".to_string(), "date".to_string()),
+            ("amount".to_string(), "3".to_string()),
+            ("description".to_string(), "Details".to_string()),
+        ];
+        assert_eq!(
+            map_row(&rows[0], &rows[1], &mappings, &specs, &layout),
+            Ok(vec![
+                ("date".to_string(), Cell::Date("2024-03-05".to_string())),
+                ("amount".to_string(), Cell::Number(-1250.5)),
+                ("description".to_string(), Cell::Text("Rent; March".to_string())),
+            ])
+        );
+        assert_eq!(map_row(&rows[0], &rows[2], &mappings, &specs, &layout).unwrap_err().len(), 2);
+    }
+}
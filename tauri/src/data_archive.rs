@@ -0,0 +1,115 @@
+//! Versioned export of the business data: a folder with one NDJSON file per table (a JSON object per row, keyed by
+//! column) and a manifest listing each file with its row count and SHA-256, so an import can refuse damaged or
+//! edited files before it changes anything.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const FORMAT: &str = "shafaf-data-archive";
+/// Archive layout version; imports read this version and older ones.
+pub const VERSION: u32 = 1;
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Tables never exported: sign-in accounts and their permission rules, API and share-link tokens, and the SQL
+/// console history.
+pub const EXCLUDED_TABLES: [&str; 5] = ["users", "permissions", "api_tokens", "share_links", "sql_console_history"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableEntry {
+    pub name: String,
+    pub file: String,
+    pub rows: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: String,
+    pub version: u32,
+    pub app_version: String,
+    pub created_at: String,
+    pub tables: Vec<TableEntry>,
+}
+
+pub fn is_excluded(table: &str) -> bool {
+    EXCLUDED_TABLES.contains(&table)
+}
+
+pub fn table_file(table: &str) -> String {
+    format!("{}.ndjson", table)
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// An entry for the NDJSON `bytes` written for `table`.
+pub fn entry(table: &str, bytes: &[u8]) -> TableEntry {
+    TableEntry {
+        name: table.to_string(),
+        file: table_file(table),
+        rows: bytes.split(|b| *b == b'\n').filter(|line| !line.is_empty()).count() as u64,
+        sha256: sha256_hex(bytes),
+    }
+}
+
+/// Whether the manifest can be imported: our format, a version this build reads, and only plain table names (the
+/// file names are joined to the archive folder).
+pub fn check(manifest: &Manifest) -> Result<(), String> {
+    if manifest.format != FORMAT {
+        return Err("Not a data archive".to_string());
+    }
+    if manifest.version == 0 || manifest.version > VERSION {
+        return Err(format!("Archive version {} is newer than this app reads ({}); update the app first", manifest.version, VERSION));
+    }
+    for table in &manifest.tables {
+        let plain = !table.name.is_empty() && table.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !plain || table.file != table_file(&table.name) {
+            return Err(format!("Invalid table in archive: {}", table.name));
+        }
+    }
+    Ok(())
+}
+
+/// Check a table file against its manifest entry.
+pub fn verify(table: &TableEntry, bytes: &[u8]) -> Result<(), String> {
+    let actual = entry(&table.name, bytes);
+    if actual.sha256 != table.sha256 {
+        return Err(format!("{} does not match the manifest (checksum); the archive is damaged or was edited", table.file));
+    }
+    if actual.rows != table.rows {
+        return Err(format!("{} has {} rows, the manifest says {}", table.file, actual.rows, table.rows));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_checks() {
+        let bytes = b"{\"id\":1,\"name\":\"Rice\"}\n{\"id\":2,\"name\":\"Tea\"}\n";
+        let products = entry("products", bytes);
+        assert_eq!(products.rows, 2);
+        assert_eq!(products.file, "products.ndjson");
+        assert!(verify(&products, bytes).is_ok());
+        assert!(verify(&products, b"{\"id\":1,\"name\":\"Rice\"}\n").is_err());
+        assert!(is_excluded("users"));
+        assert!(!is_excluded("sales"));
+
+        let mut manifest = Manifest {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            app_version: "1.0.0".to_string(),
+            created_at: "2024-02-01 10:00:00".to_string(),
+            tables: vec![products],
+        };
+        assert!(check(&manifest).is_ok());
+        manifest.tables[0].name = "../products".to_string();
+        assert!(check(&manifest).is_err());
+        manifest.tables.clear();
+        manifest.version = VERSION + 1;
+        assert!(check(&manifest).is_err());
+    }
+}
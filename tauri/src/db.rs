@@ -1,14 +1,166 @@
-use mysql::{Conn, Opts, prelude::*};
-use std::sync::Mutex;
+use mysql::{Conn, Opts, OptsBuilder, Pool, PoolConstraints, PoolOpts, PooledConn, Statement, Transaction, TxOpts, prelude::*};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::ThreadId;
+use std::time::Duration;
 use anyhow::Result;
 
+/// Reconnect attempts after a lost connection before the error is returned to the caller.
+const MAX_RECONNECT_ATTEMPTS: u32 = 3;
+/// Pause before reconnect attempt n is n times this.
+const RECONNECT_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Count a MySQL error for /metrics and convert it for `?`.
+fn db_error(e: mysql::Error) -> anyhow::Error {
+    crate::metrics::inc_db_errors();
+    e.into()
+}
+
+/// Errors meaning the server connection is gone (server restarted, network dropped, idle timeout).
+fn is_connection_error(e: &mysql::Error) -> bool {
+    match e {
+        mysql::Error::IoError(_) | mysql::Error::CodecError(_) => true,
+        // CR_SERVER_GONE_ERROR, CR_SERVER_LOST, ER_SERVER_SHUTDOWN, ER_CLIENT_INTERACTION_TIMEOUT
+        mysql::Error::MySqlError(e) => matches!(e.code, 2006 | 2013 | 1053 | 4031),
+        _ => false,
+    }
+}
+
+/// Errors after which the server rolled the statement or transaction back and running it again can succeed:
+/// ER_LOCK_DEADLOCK and ER_LOCK_WAIT_TIMEOUT.
+fn is_transient_error(e: &mysql::Error) -> bool {
+    matches!(e, mysql::Error::MySqlError(e) if matches!(e.code, 1213 | 1205))
+}
+
+/// How often reads and transaction blocks are retried after a transient error. Set with DB_RETRY_ATTEMPTS and
+/// DB_RETRY_BACKOFF_MS (pause before the first retry, doubled for each further one).
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_env() -> Self {
+        let var = |key: &str| std::env::var(key).ok().and_then(|v| v.trim().parse::<u64>().ok());
+        RetryPolicy {
+            attempts: var("DB_RETRY_ATTEMPTS").map_or(3, |n| n.min(10) as u32),
+            backoff: Duration::from_millis(var("DB_RETRY_BACKOFF_MS").unwrap_or(100)),
+        }
+    }
+
+    /// Pause before retry `n` (1-based).
+    fn delay(&self, n: u32) -> Duration {
+        self.backoff * (1u32 << n.saturating_sub(1).min(10))
+    }
+}
+
+fn not_open() -> anyhow::Error {
+    anyhow::anyhow!("Database is not open. Please open it first.")
+}
+
+/// Connectivity as seen by the last statement or health check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionState {
+    Connected,
+    /// The connection was lost and is being re-established.
+    Degraded,
+    /// Reconnecting failed; statements fail until the server is back.
+    Disconnected,
+}
+
+type StateListener = Box<dyn Fn(ConnectionState) + Send + Sync>;
+
+/// How long a statement waits for a free pooled connection before failing.
+const POOL_WAIT: Duration = Duration::from_secs(10);
+
+/// Connections the pool keeps open (MYSQL_POOL_MIN, default 2) and at most opens (MYSQL_POOL_MAX, default 10).
+#[derive(Debug, Clone, Copy)]
+pub struct PoolSize {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl PoolSize {
+    pub fn from_env() -> Self {
+        let var = |key: &str| std::env::var(key).ok().and_then(|v| v.trim().parse::<usize>().ok());
+        let max = var("MYSQL_POOL_MAX").unwrap_or(10).clamp(1, 100);
+        PoolSize { min: var("MYSQL_POOL_MIN").unwrap_or(2).min(max), max }
+    }
+}
+
+/// Pool usage for the db_pool_status command.
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolStatus {
+    pub min: usize,
+    pub max: usize,
+    /// Connections lent out right now, open transactions included.
+    pub in_use: usize,
+    pub open_transactions: usize,
+    /// Connections handed out since the database was opened.
+    pub checkouts: u64,
+    /// Statements that gave up waiting for a free connection.
+    pub wait_timeouts: u64,
+    pub state: ConnectionState,
+}
+
+/// A pooled connection, counted as in use until it is dropped and goes back to the pool.
+struct Lease {
+    conn: PooledConn,
+    in_use: Arc<AtomicUsize>,
+}
+
+impl Deref for Lease {
+    type Target = Conn;
+
+    fn deref(&self) -> &Conn {
+        &self.conn
+    }
+}
+
+impl DerefMut for Lease {
+    fn deref_mut(&mut self) -> &mut Conn {
+        &mut self.conn
+    }
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        self.in_use.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// The connection an atomic block runs on; every statement of the thread that opened it goes to it.
+struct Pinned {
+    /// None while a statement of the block is running on it.
+    conn: Option<Lease>,
+    /// The server already ended the transaction (connection lost, deadlock), so nothing more may run in it.
+    aborted: bool,
+}
+
 pub struct Database {
-    conn: Mutex<Option<Conn>>,
+    pool: Mutex<Option<Pool>>,
     opts: Opts,
+    size: PoolSize,
     /// Connection info for display (e.g. "host/database")
     connection_info: String,
+    state: Mutex<ConnectionState>,
+    listener: Mutex<Option<StateListener>>,
+    retry: RetryPolicy,
+    pinned: Mutex<HashMap<ThreadId, Pinned>>,
+    /// Held by the outermost atomic block, so document numbers taken as MAX()+1 inside them stay unique.
+    writer: Mutex<()>,
+    in_use: Arc<AtomicUsize>,
+    checkouts: AtomicU64,
+    wait_timeouts: AtomicU64,
 }
 
+const TRANSACTION_ABORTED: &str = "Transaction was aborted; its changes have been rolled back";
+
 impl Database {
     pub fn new(opts: Opts) -> Self {
         let connection_info = format!(
@@ -17,46 +169,216 @@ impl Database {
             opts.get_db_name().unwrap_or("")
         );
         Database {
-            conn: Mutex::new(None),
+            pool: Mutex::new(None),
             opts,
+            size: PoolSize::from_env(),
             connection_info,
+            state: Mutex::new(ConnectionState::Connected),
+            listener: Mutex::new(None),
+            retry: RetryPolicy::from_env(),
+            pinned: Mutex::new(HashMap::new()),
+            writer: Mutex::new(()),
+            in_use: Arc::new(AtomicUsize::new(0)),
+            checkouts: AtomicU64::new(0),
+            wait_timeouts: AtomicU64::new(0),
+        }
+    }
+
+    /// Call `f` whenever the connection state changes (e.g. to notify the UI).
+    pub fn on_state_change(&self, f: impl Fn(ConnectionState) + Send + Sync + 'static) {
+        *self.listener.lock().unwrap() = Some(Box::new(f));
+    }
+
+    pub fn connection_state(&self) -> ConnectionState {
+        *self.state.lock().unwrap()
+    }
+
+    fn set_state(&self, state: ConnectionState) {
+        let changed = {
+            let mut current = self.state.lock().unwrap();
+            std::mem::replace(&mut *current, state) != state
+        };
+        if changed {
+            if let Some(listener) = self.listener.lock().unwrap().as_ref() {
+                listener(state);
+            }
+        }
+    }
+
+    pub fn pool_status(&self) -> PoolStatus {
+        PoolStatus {
+            min: self.size.min,
+            max: self.size.max,
+            in_use: self.in_use.load(Ordering::Relaxed),
+            open_transactions: self.pinned.lock().unwrap().len(),
+            checkouts: self.checkouts.load(Ordering::Relaxed),
+            wait_timeouts: self.wait_timeouts.load(Ordering::Relaxed),
+            state: self.connection_state(),
+        }
+    }
+
+    /// Take a connection from the pool, which pings it first and replaces it if it died while idle.
+    fn checkout(&self) -> Result<Lease> {
+        let pool = self.pool.lock().unwrap().clone().ok_or_else(not_open)?;
+        match pool.try_get_conn(POOL_WAIT) {
+            Ok(conn) => {
+                self.in_use.fetch_add(1, Ordering::Relaxed);
+                self.checkouts.fetch_add(1, Ordering::Relaxed);
+                Ok(Lease { conn, in_use: self.in_use.clone() })
+            }
+            Err(mysql::Error::DriverError(mysql::DriverError::Timeout)) => {
+                self.wait_timeouts.fetch_add(1, Ordering::Relaxed);
+                Err(anyhow::anyhow!("All {} database connections are busy; try again shortly", self.size.max))
+            }
+            Err(e) => Err(db_error(e)),
+        }
+    }
+
+    /// Get a working connection after the server was lost, retrying with backoff.
+    fn reconnect(&self) -> Option<Lease> {
+        self.set_state(ConnectionState::Degraded);
+        for attempt in 1..=MAX_RECONNECT_ATTEMPTS {
+            std::thread::sleep(RECONNECT_BACKOFF * attempt);
+            if let Ok(conn) = self.checkout() {
+                self.set_state(ConnectionState::Connected);
+                return Some(conn);
+            }
+        }
+        self.set_state(ConnectionState::Disconnected);
+        None
+    }
+
+    /// A connection for a statement: the calling thread's transaction connection inside an atomic block,
+    /// otherwise one from the pool (waiting for the server to come back if it was lost).
+    fn connection(&self) -> Result<(Lease, bool)> {
+        let pinned = match self.pinned.lock().unwrap().get_mut(&std::thread::current().id()) {
+            Some(pinned) if pinned.aborted => return Err(anyhow::anyhow!(TRANSACTION_ABORTED)),
+            Some(pinned) => Some(pinned.conn.take().ok_or_else(|| anyhow::anyhow!("The transaction connection is already in use"))?),
+            None => None,
+        };
+        if let Some(conn) = pinned {
+            return Ok((conn, true));
+        }
+        match self.checkout() {
+            Ok(conn) => Ok((conn, false)),
+            Err(e) if e.downcast_ref::<mysql::Error>().is_some_and(is_connection_error) => self.reconnect().map(|c| (c, false)).ok_or(e),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Give a transaction connection taken by `connection` back to its atomic block.
+    fn unpin(&self, conn: Lease, aborted: bool) {
+        if let Some(pinned) = self.pinned.lock().unwrap().get_mut(&std::thread::current().id()) {
+            pinned.conn = Some(conn);
+            pinned.aborted |= aborted;
+        }
+    }
+
+    /// Prepare `sql` and run `op` with it. A lost connection is re-established and the statement retried once;
+    /// when `op` itself failed (the statement may have reached the server) it is only retried if `idempotent`.
+    /// Idempotent statements are also retried per the retry policy after a deadlock or lock wait timeout.
+    /// Statements inside an atomic block are never retried: the server has already rolled the transaction back.
+    fn run<T>(&self, sql: &str, idempotent: bool, mut op: impl FnMut(&mut Conn, &Statement) -> Result<T>) -> Result<T> {
+        let (mut conn, pinned) = self.connection()?;
+        let mut retried = false;
+        let mut transient_retries = 0;
+        loop {
+            let (err, executed) = match conn.prep(sql) {
+                Ok(stmt) => match op(&mut conn, &stmt) {
+                    Ok(v) => {
+                        self.set_state(ConnectionState::Connected);
+                        if pinned {
+                            self.unpin(conn, false);
+                        }
+                        return Ok(v);
+                    }
+                    Err(e) => (e, true),
+                },
+                Err(e) => (e.into(), false),
+            };
+            let lost = err.downcast_ref::<mysql::Error>().is_some_and(is_connection_error);
+            let transient = err.downcast_ref::<mysql::Error>().is_some_and(is_transient_error);
+            if pinned {
+                self.unpin(conn, lost || transient);
+            } else {
+                if lost && !retried && (idempotent || !executed) {
+                    if let Some(fresh) = self.reconnect() {
+                        conn = fresh;
+                        retried = true;
+                        continue;
+                    }
+                }
+                if transient && idempotent && transient_retries < self.retry.attempts {
+                    transient_retries += 1;
+                    std::thread::sleep(self.retry.delay(transient_retries));
+                    continue;
+                }
+            }
+            return Err(match err.downcast::<mysql::Error>() {
+                Ok(e) => db_error(e),
+                Err(e) => e,
+            });
         }
     }
 
-    /// Open the MySQL connection using stored opts.
+    /// Ping the server and reconnect if the connection is gone. Returns the resulting state.
+    pub fn health_check(&self) -> ConnectionState {
+        if self.is_open() {
+            match self.checkout().and_then(|mut conn| Ok(conn.query_drop("DO 1")?)) {
+                Ok(()) => self.set_state(ConnectionState::Connected),
+                Err(_) => {
+                    self.reconnect();
+                }
+            }
+        }
+        self.connection_state()
+    }
+
+    /// Open the connection pool using stored opts; MYSQL_POOL_MIN connections are made right away.
     pub fn open(&self) -> Result<()> {
-        let mut conn_guard = self.conn.lock().unwrap();
-        if conn_guard.is_some() {
+        let mut pool_guard = self.pool.lock().unwrap();
+        if pool_guard.is_some() {
             return Ok(());
         }
-        let conn = Conn::new(self.opts.clone())?;
-        *conn_guard = Some(conn);
+        let constraints = PoolConstraints::new(self.size.min, self.size.max)
+            .ok_or_else(|| anyhow::anyhow!("MYSQL_POOL_MIN must not be larger than MYSQL_POOL_MAX"))?;
+        let opts = OptsBuilder::from_opts(self.opts.clone()).pool_opts(PoolOpts::default().with_constraints(constraints));
+        *pool_guard = Some(Pool::new(opts)?);
         Ok(())
     }
 
-    /// Close the database connection.
+    /// Close the pool; connections still lent out are closed when they are returned.
     pub fn close(&self) -> Result<()> {
-        let mut conn_guard = self.conn.lock().unwrap();
-        if let Some(conn) = conn_guard.take() {
-            drop(conn);
+        let mut pool_guard = self.pool.lock().unwrap();
+        if let Some(pool) = pool_guard.take() {
+            drop(pool);
         }
         Ok(())
     }
 
     /// Check if database is open.
     pub fn is_open(&self) -> bool {
-        let conn_guard = self.conn.lock().unwrap();
-        conn_guard.is_some()
+        let pool_guard = self.pool.lock().unwrap();
+        pool_guard.is_some()
     }
 
     /// Execute a SQL query that doesn't return results.
     /// Params: pass values that implement Into<mysql::Params> (e.g. (), (a, b), or vec of Value).
     pub fn execute<P: Into<mysql::Params>>(&self, sql: &str, params: P) -> Result<usize> {
-        let mut conn_guard = self.conn.lock().unwrap();
-        let conn = conn_guard.as_mut().ok_or_else(|| anyhow::anyhow!("Database is not open. Please open it first."))?;
-        let stmt = conn.prep(sql)?;
-        conn.exec_drop(&stmt, params)?;
-        Ok(conn.affected_rows() as usize)
+        let params: mysql::Params = params.into();
+        self.run(sql, false, |conn, stmt| {
+            conn.exec_drop(stmt, params.clone())?;
+            Ok(conn.affected_rows() as usize)
+        })
+    }
+
+    /// Execute an INSERT and return the auto-increment id generated on the same connection.
+    pub fn insert<P: Into<mysql::Params>>(&self, sql: &str, params: P) -> Result<i64> {
+        let params: mysql::Params = params.into();
+        self.run(sql, false, |conn, stmt| {
+            conn.exec_drop(stmt, params.clone())?;
+            Ok(conn.last_insert_id() as i64)
+        })
     }
 
     /// Execute a SQL query and return results; map each row with f.
@@ -65,37 +387,109 @@ impl Database {
         P: Into<mysql::Params>,
         F: FnMut(&mysql::Row) -> Result<T>,
     {
-        let mut conn_guard = self.conn.lock().unwrap();
-        let conn = conn_guard.as_mut().ok_or_else(|| anyhow::anyhow!("Database is not open. Please open it first."))?;
-        let stmt = conn.prep(sql)?;
-        let mut result = conn.exec_iter(&stmt, params)?;
-        let mut rows = Vec::new();
-        if let Some(rows_iter) = result.iter() {
-            for row in rows_iter {
-                let row = row?;
-                rows.push(f(&row)?);
+        let params: mysql::Params = params.into();
+        self.run(sql, true, |conn, stmt| {
+            let mut result = conn.exec_iter(stmt, params.clone())?;
+            let mut rows = Vec::new();
+            if let Some(rows_iter) = result.iter() {
+                for row in rows_iter {
+                    rows.push(f(&row?)?);
+                }
             }
-        }
-        Ok(rows)
+            Ok(rows)
+        })
     }
 
     /// Get column names from a prepared statement (prep only, no execute).
     pub fn get_columns(&self, sql: &str) -> Result<Vec<String>> {
-        let mut conn_guard = self.conn.lock().unwrap();
-        let conn = conn_guard.as_mut().ok_or_else(|| anyhow::anyhow!("Database is not open. Please open it first."))?;
-        let stmt = conn.prep(sql)?;
-        let columns = stmt.columns().iter().map(|c| c.name_str().to_string()).collect();
-        Ok(columns)
+        self.run(sql, true, |_, stmt| Ok(stmt.columns().iter().map(|c| c.name_str().to_string()).collect()))
     }
 
-    /// Get connection for advanced operations (internal use).
+    /// Get connection for advanced operations (internal use). `f` runs on one connection and is not retried;
+    /// inside an atomic block it is the block's transaction connection.
     pub fn with_connection<F, R>(&self, f: F) -> Result<R>
     where
         F: FnOnce(&mut Conn) -> Result<R>,
     {
-        let mut conn_guard = self.conn.lock().unwrap();
-        let conn = conn_guard.as_mut().ok_or_else(|| anyhow::anyhow!("Database is not open. Please open it first."))?;
-        f(conn)
+        let (mut conn, pinned) = self.connection()?;
+        let result = f(&mut conn);
+        if pinned {
+            self.unpin(conn, false);
+        }
+        result
+    }
+
+    fn in_atomic(&self) -> bool {
+        self.pinned.lock().unwrap().contains_key(&std::thread::current().id())
+    }
+
+    /// Run `f` in a transaction and commit it. A transaction the server aborted with a deadlock or lock wait timeout
+    /// is rolled back and `f` run again per the retry policy, so `f` must not have effects outside the transaction.
+    pub fn with_transaction<F, R>(&self, mut f: F) -> Result<R>
+    where
+        F: FnMut(&mut Transaction<'_>) -> Result<R>,
+    {
+        if self.in_atomic() {
+            // START TRANSACTION would commit the atomic block's work so far
+            return Err(anyhow::anyhow!("with_transaction cannot run inside an atomic block"));
+        }
+        let (mut conn, _) = self.connection()?;
+        let mut retries = 0;
+        loop {
+            // Dropping an uncommitted transaction rolls it back
+            let result = conn.start_transaction(TxOpts::default()).map_err(anyhow::Error::from).and_then(|mut tx| {
+                let value = f(&mut tx)?;
+                tx.commit()?;
+                Ok(value)
+            });
+            match result {
+                Err(e) if retries < self.retry.attempts && e.downcast_ref::<mysql::Error>().is_some_and(is_transient_error) => {
+                    retries += 1;
+                    std::thread::sleep(self.retry.delay(retries));
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Run `f` as one transaction: everything it writes through execute, insert and query is committed when it
+    /// returns Ok and rolled back when it returns Err, so a failure half-way leaves nothing behind. The statements
+    /// run on one connection reserved for the calling thread, and blocks nested inside it join the outer
+    /// transaction. `f` must not use with_transaction, and DDL (CREATE/ALTER TABLE) in it commits implicitly.
+    pub fn atomic<R>(&self, f: impl FnOnce() -> std::result::Result<R, String>) -> std::result::Result<R, String> {
+        if self.in_atomic() {
+            return f();
+        }
+
+        // Rolls the transaction back if `f` panics
+        struct Rollback<'a>(&'a Database, ThreadId);
+        impl Drop for Rollback<'_> {
+            fn drop(&mut self) {
+                let pinned = self.0.pinned.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.1);
+                if let Some(mut conn) = pinned.and_then(|p| p.conn) {
+                    let _ = conn.query_drop("ROLLBACK");
+                }
+            }
+        }
+
+        let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let (mut conn, _) = self.connection().map_err(|e| format!("Failed to start transaction: {}", e))?;
+        conn.query_drop("START TRANSACTION").map_err(|e| format!("Failed to start transaction: {}", e))?;
+        let thread = std::thread::current().id();
+        self.pinned.lock().unwrap().insert(thread, Pinned { conn: Some(conn), aborted: false });
+        let rollback = Rollback(self, thread);
+        let result = f();
+        let pinned = self.pinned.lock().unwrap().remove(&thread);
+        drop(rollback);
+        let Some(Pinned { conn: Some(mut conn), aborted }) = pinned else {
+            return result.and(Err(TRANSACTION_ABORTED.to_string()));
+        };
+        if result.is_err() || aborted {
+            let _ = conn.query_drop("ROLLBACK");
+            return result.and(Err(TRANSACTION_ABORTED.to_string()));
+        }
+        conn.query_drop("COMMIT").map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        result
     }
 
     /// Get connection info string (e.g. "127.0.0.1/dbname").
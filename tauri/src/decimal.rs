@@ -0,0 +1,72 @@
+//! Exact money arithmetic with rust_decimal: amounts are taken at the scale of the DECIMAL(18,4) money columns, so
+//! adding up many lines gives the same total the database stores instead of drifting by cents. Unit prices and costs
+//! are DECIMAL(18,6) so per-unit figures derived by division keep their precision. Totals, balances and roundings go
+//! through Decimal; record structs still carry f64 at the command/JSON boundary.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::RoundingStrategy;
+use std::str::FromStr;
+
+pub use rust_decimal::Decimal;
+
+/// Decimal places of the money columns.
+pub const SCALE: u32 = 4;
+
+/// The amount `x` stands for at the column scale. It is read from the shortest text of the f64 (0.1 is 0.1, not
+/// 0.1000000000000000055...), so ties round as written.
+pub fn amount(x: f64) -> Decimal {
+    if !x.is_finite() {
+        return Decimal::ZERO;
+    }
+    Decimal::from_str(&x.to_string())
+        .map(|d| d.round_dp_with_strategy(SCALE, RoundingStrategy::MidpointAwayFromZero))
+        .unwrap_or(Decimal::ZERO)
+}
+
+pub fn to_f64(d: Decimal) -> f64 {
+    d.to_f64().unwrap_or(0.0)
+}
+
+/// A DECIMAL column's text ("1250.5000") as a number; None when it is not one.
+pub fn parse(text: &str) -> Option<f64> {
+    Decimal::from_str(text.trim()).ok().and_then(|d| d.to_f64())
+}
+
+/// Sum of amounts, each taken at the column scale first.
+pub fn sum<I: IntoIterator<Item = f64>>(values: I) -> f64 {
+    to_f64(values.into_iter().map(amount).sum::<Decimal>())
+}
+
+/// `x` rounded half away from zero to `decimals` places, deciding ties on the decimal value (1.005 becomes 1.01,
+/// where binary rounding gives 1.00). Places beyond the scale round the f64 directly.
+pub fn round(x: f64, decimals: u32) -> f64 {
+    if decimals > SCALE {
+        let factor = 10f64.powi(decimals as i32);
+        return (x * factor).round() / factor;
+    }
+    to_f64(amount(x).round_dp_with_strategy(decimals, RoundingStrategy::MidpointAwayFromZero))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_penny_exact_totals() {
+        // A long invoice: 1000 lines of 0.10 and 1000 of 0.20 add up to exactly 300
+        let lines: Vec<f64> = (0..2000).map(|i| if i % 2 == 0 { 0.1 } else { 0.2 }).collect();
+        assert_ne!(lines.iter().sum::<f64>(), 300.0);
+        assert_eq!(sum(lines.iter().copied()), 300.0);
+        // 3 × 33.33 + 0.01 per line, 999 lines
+        let lines: Vec<f64> = (0..999).map(|_| 33.33 * 3.0 + 0.01).collect();
+        assert_eq!(sum(lines), 99_900.0);
+        assert_eq!(round(1.005, 2), 1.01);
+        assert_eq!(round(-2.5, 0), -3.0);
+        assert_eq!(round(2.675, 2), 2.68);
+        assert_eq!(round(12.34567, 6), 12.34567);
+        assert_eq!(round(12.34567, 4), 12.3457);
+        assert_eq!(amount(0.3) - amount(0.1), amount(0.2));
+        assert_eq!(parse("1250.5000"), Some(1250.5));
+        assert_eq!(parse("n/a"), None);
+    }
+}
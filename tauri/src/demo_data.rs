@@ -0,0 +1,308 @@
+//! Deterministic demo data: a year of trading for a small Kabul grocery wholesaler, planned in memory from a fixed
+//! seed so the same scale always gives the same rows. Sales only take stock that earlier purchases brought in, and
+//! each sale line names the batch it comes from (oldest first).
+
+/// Length of the generated history in days.
+pub const DAYS: u32 = 365;
+
+const SEED: u64 = 0x5348_4146_4146; // "SHAFAF"
+
+/// Catalog: name, category and typical cost in afghani.
+const CATALOG: [(&str, &str, f64); 30] = [
+    ("برنج سیلا ۵ کیلو", "خوراکه", 520.0),
+    ("برنج سیلا ۲۵ کیلو", "خوراکه", 2450.0),
+    ("آرد قزاقستانی ۵۰ کیلو", "خوراکه", 1850.0),
+    ("روغن آفتاب‌پرست ۵ لیتر", "خوراکه", 780.0),
+    ("روغن نباتی ۱۶ کیلو", "خوراکه", 1950.0),
+    ("بوره ۵۰ کیلو", "خوراکه", 2900.0),
+    ("چای سبز ۵۰۰ گرم", "نوشیدنی", 210.0),
+    ("چای سیاه ۵۰۰ گرم", "نوشیدنی", 240.0),
+    ("نخود ۱ کیلو", "حبوبات", 95.0),
+    ("لوبیا ۱ کیلو", "حبوبات", 120.0),
+    ("دال نسک ۱ کیلو", "حبوبات", 110.0),
+    ("ماش ۱ کیلو", "حبوبات", 105.0),
+    ("نمک ۱ کیلو", "خوراکه", 25.0),
+    ("رب گوجه ۸۰۰ گرم", "کنسرو", 85.0),
+    ("ماکارونی ۵۰۰ گرم", "خوراکه", 45.0),
+    ("شیر خشک ۴۰۰ گرم", "لبنیات", 260.0),
+    ("پنیر ۵۰۰ گرم", "لبنیات", 190.0),
+    ("عسل ۱ کیلو", "خوراکه", 650.0),
+    ("کشمش ۱ کیلو", "خشکبار", 320.0),
+    ("بادام ۱ کیلو", "خشکبار", 780.0),
+    ("صابون کالا ۱۲ عدد", "شوینده", 280.0),
+    ("پودر لباسشویی ۳ کیلو", "شوینده", 340.0),
+    ("شامپو ۷۵۰ میلی‌لیتر", "شوینده", 180.0),
+    ("دستمال کاغذی ۱۰ بسته", "شوینده", 150.0),
+    ("آب معدنی ۱۲ بوتل", "نوشیدنی", 120.0),
+    ("نوشابه ۶ بوتل", "نوشیدنی", 210.0),
+    ("بیسکویت کارتن", "تنقلات", 560.0),
+    ("چیپس کارتن", "تنقلات", 480.0),
+    ("گوگرد ۱۰ قطی", "متفرقه", 30.0),
+    ("باتری قلمی ۴ عدد", "متفرقه", 90.0),
+];
+
+const FIRST_NAMES: [&str; 20] = [
+    "احمد", "محمود", "کریم", "فرید", "نصیر", "حمید", "جاوید", "ولی", "شریف", "رحیم", "زلمی", "عزیز", "بشیر", "قدیر",
+    "فاطمه", "مریم", "زهرا", "شبنم", "لیلا", "ناهید",
+];
+
+const LAST_NAMES: [&str; 14] = [
+    "احمدی", "رحیمی", "کریمی", "نوری", "حیدری", "صدیقی", "عزیزی", "قاسمی", "یوسفزی", "پوپل", "هاشمی", "سلطانی",
+    "محمدی", "واعظی",
+];
+
+const CITY_AREAS: [&str; 8] = ["کارته چهار", "شهر نو", "خیرخانه", "مکرویان", "دشت برچی", "کارته پروان", "تایمنی", "کوته سنگی"];
+
+const SUPPLIERS: [&str; 5] = ["شرکت تجارتی برادران", "مارکیت عمده فروشی کابل", "شرکت وارداتی آریانا", "تجارتخانه هرات", "شرکت توزیعی شمال"];
+
+/// Expense types: name, monthly day (None = occasional) and typical amount.
+pub const EXPENSE_TYPES: [(&str, Option<u32>, f64); 5] = [
+    ("کرایه دکان", Some(1), 25000.0),
+    ("برق", Some(10), 3500.0),
+    ("معاش کارمندان", Some(28), 60000.0),
+    ("ترانسپورت", None, 1800.0),
+    ("متفرقه", None, 900.0),
+];
+
+/// SplitMix64: small, fast and the same on every platform.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// 0..n
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n.max(1) as u64) as usize
+    }
+
+    /// Uniform in lo..hi.
+    pub fn between(&mut self, lo: f64, hi: f64) -> f64 {
+        lo + (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64 * (hi - lo)
+    }
+
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.between(0.0, 1.0) < p
+    }
+
+    fn phone(&mut self) -> String {
+        format!("07{}{:07}", 7 + self.below(3), self.below(10_000_000))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemoProduct {
+    pub name: &'static str,
+    pub category: &'static str,
+    pub cost: f64,
+    pub price: f64,
+    /// Index into the plan's suppliers.
+    pub supplier: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemoParty {
+    pub name: String,
+    pub phone: String,
+    pub address: String,
+}
+
+/// A purchase on `day` (0 = first day of the history); items are (product, amount, unit cost).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemoPurchase {
+    pub day: u32,
+    pub supplier: usize,
+    pub items: Vec<(usize, f64, f64)>,
+}
+
+/// A sale line; `batch` is (purchase index, item index) of the batch it is taken from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemoSaleItem {
+    pub product: usize,
+    pub batch: (usize, usize),
+    pub amount: f64,
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemoSale {
+    pub day: u32,
+    pub customer: usize,
+    pub items: Vec<DemoSaleItem>,
+    pub paid: f64,
+}
+
+impl DemoSale {
+    pub fn total(&self) -> f64 {
+        self.items.iter().map(|i| i.amount * i.price).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemoExpense {
+    pub day: u32,
+    /// Index into EXPENSE_TYPES.
+    pub kind: usize,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemoPlan {
+    pub products: Vec<DemoProduct>,
+    pub suppliers: Vec<DemoParty>,
+    pub customers: Vec<DemoParty>,
+    /// In date order, like the sales and expenses.
+    pub purchases: Vec<DemoPurchase>,
+    pub sales: Vec<DemoSale>,
+    pub expenses: Vec<DemoExpense>,
+}
+
+fn round0(v: f64) -> f64 {
+    v.round()
+}
+
+fn party(rng: &mut Rng, name: String) -> DemoParty {
+    let address = format!("کابل، {}", CITY_AREAS[rng.below(CITY_AREAS.len())]);
+    DemoParty { name, phone: rng.phone(), address }
+}
+
+/// The demo history for `scale` (1 = a small shop: 25 customers and about 6 sales a day; volume grows linearly).
+pub fn plan(scale: u32) -> DemoPlan {
+    let scale = scale.max(1);
+    let mut rng = Rng::new(SEED ^ scale as u64);
+
+    let suppliers: Vec<DemoParty> = SUPPLIERS.iter().map(|s| party(&mut rng, s.to_string())).collect();
+    let products: Vec<DemoProduct> = CATALOG
+        .iter()
+        .enumerate()
+        .map(|(i, (name, category, cost))| {
+            let cost = round0(cost * rng.between(0.9, 1.1));
+            DemoProduct { name, category, cost, price: round0(cost * rng.between(1.12, 1.3)), supplier: i % suppliers.len() }
+        })
+        .collect();
+    let customers: Vec<DemoParty> = (0..25 * scale as usize)
+        .map(|_| {
+            let name = format!("{} {}", FIRST_NAMES[rng.below(FIRST_NAMES.len())], LAST_NAMES[rng.below(LAST_NAMES.len())]);
+            party(&mut rng, name)
+        })
+        .collect();
+
+    // Open batches per product, oldest first: (purchase, item, remaining)
+    let mut stock: Vec<Vec<(usize, usize, f64)>> = vec![Vec::new(); products.len()];
+    let target = 30.0 * scale as f64;
+    let mut purchases = Vec::new();
+    let mut sales = Vec::new();
+    let mut expenses = Vec::new();
+
+    for day in 0..DAYS {
+        // Weekly restock from each supplier up to twice the target level, at a slowly rising cost
+        if day % 7 == 0 {
+            let inflation = 1.0 + day as f64 / DAYS as f64 * 0.06;
+            for supplier in 0..suppliers.len() {
+                let mut items = Vec::new();
+                for (p, product) in products.iter().enumerate().filter(|(_, p)| p.supplier == supplier) {
+                    let on_hand: f64 = stock[p].iter().map(|b| b.2).sum();
+                    if on_hand < target {
+                        let amount = (target * 2.0 - on_hand).ceil();
+                        let cost = round0(product.cost * inflation * rng.between(0.96, 1.04));
+                        stock[p].push((purchases.len(), items.len(), amount));
+                        items.push((p, amount, cost));
+                    }
+                }
+                if !items.is_empty() {
+                    purchases.push(DemoPurchase { day, supplier, items });
+                }
+            }
+        }
+
+        let count = 2 * scale as usize + rng.below(6 * scale as usize);
+        for _ in 0..count {
+            let mut items = Vec::new();
+            let mut used = Vec::new();
+            for _ in 0..1 + rng.below(4) {
+                let p = rng.below(products.len());
+                if used.contains(&p) {
+                    continue;
+                }
+                used.push(p);
+                let mut amount = (1 + rng.below(5)) as f64;
+                let price = round0(products[p].price * rng.between(0.97, 1.03));
+                for batch in stock[p].iter_mut() {
+                    if amount <= 0.0 {
+                        break;
+                    }
+                    let take = amount.min(batch.2);
+                    if take > 0.0 {
+                        batch.2 -= take;
+                        amount -= take;
+                        items.push(DemoSaleItem { product: p, batch: (batch.0, batch.1), amount: take, price });
+                    }
+                }
+                stock[p].retain(|b| b.2 > 0.0);
+            }
+            if items.is_empty() {
+                continue;
+            }
+            let mut sale = DemoSale { day, customer: rng.below(customers.len()), items, paid: 0.0 };
+            let total = sale.total();
+            // Most sales are paid in cash, some half on credit, a few fully on credit
+            sale.paid = match rng.below(20) {
+                0 => 0.0,
+                1..=4 => round0(total / 2.0),
+                _ => total,
+            };
+            sales.push(sale);
+        }
+
+        for (kind, (_, monthly_day, amount)) in EXPENSE_TYPES.iter().enumerate() {
+            let due = match monthly_day {
+                Some(d) => day % 30 == d % 30,
+                None => rng.chance(0.15),
+            };
+            if due {
+                expenses.push(DemoExpense { day, kind, amount: round0(amount * rng.between(0.8, 1.2)) });
+            }
+        }
+    }
+
+    DemoPlan { products, suppliers, customers, purchases, sales, expenses }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan() {
+        let a = plan(1);
+        assert_eq!(a, plan(1));
+        assert_ne!(a.sales, plan(2).sales);
+        assert_eq!(a.customers.len(), 25);
+        assert!(a.sales.len() > 365 * 2);
+        assert!(a.expenses.iter().filter(|e| e.kind == 0).count() >= 12);
+
+        // Every sale line comes from an earlier (or same-day) purchase of the same product, and no batch is oversold
+        let mut sold = std::collections::HashMap::new();
+        for sale in &a.sales {
+            assert!(sale.paid <= sale.total());
+            for item in &sale.items {
+                let purchase = &a.purchases[item.batch.0];
+                let (product, amount, _) = purchase.items[item.batch.1];
+                assert_eq!(product, item.product);
+                assert!(purchase.day <= sale.day);
+                let total = sold.entry(item.batch).or_insert(0.0);
+                *total += item.amount;
+                assert!(*total <= amount);
+            }
+        }
+    }
+}
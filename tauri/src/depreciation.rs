@@ -0,0 +1,101 @@
+//! Depreciation schedules for the asset register: monthly amounts from cost, salvage value and useful life.
+//! Depreciation starts in the month the asset was acquired.
+
+/// Supported methods.
+pub const METHODS: [&str; 2] = ["straight_line", "declining_balance"];
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+/// Monthly depreciation amounts over the useful life; they add up to cost - salvage exactly (the last month absorbs
+/// rounding). "declining_balance" uses the double-declining rate 2 / life and never goes below salvage.
+pub fn monthly_schedule(cost: f64, salvage: f64, life_months: i64, method: &str) -> Vec<f64> {
+    let depreciable = round2((cost - salvage).max(0.0));
+    if life_months <= 0 || depreciable <= 0.0 {
+        return Vec::new();
+    }
+    let n = life_months as usize;
+    let mut amounts = Vec::with_capacity(n);
+    let mut total = 0.0;
+    if method == "declining_balance" {
+        let rate = 2.0 / life_months as f64;
+        let mut book = cost;
+        for _ in 0..n - 1 {
+            let amount = round2((book * rate).min(book - salvage).max(0.0));
+            book -= amount;
+            total += amount;
+            amounts.push(amount);
+        }
+    } else {
+        let per_month = round2(depreciable / life_months as f64);
+        for _ in 0..n - 1 {
+            total += per_month;
+            amounts.push(per_month);
+        }
+    }
+    amounts.push(round2(depreciable - total).max(0.0));
+    amounts
+}
+
+/// Zero-based month index of `period` ("YYYY-MM") relative to the month of `start` ("YYYY-MM-DD" or "YYYY-MM").
+pub fn month_index(start: &str, period: &str) -> Option<i64> {
+    let parse = |s: &str| -> Option<(i64, i64)> {
+        let mut parts = s.trim().split('-');
+        let year = parts.next()?.parse::<i64>().ok()?;
+        let month = parts.next()?.get(0..2)?.parse::<i64>().ok()?;
+        (1..=12).contains(&month).then_some((year, month))
+    };
+    let (sy, sm) = parse(start)?;
+    let (py, pm) = parse(period)?;
+    Some((py - sy) * 12 + (pm - sm))
+}
+
+/// Last calendar day of a "YYYY-MM" period as "YYYY-MM-DD" (date of the month's depreciation entry).
+pub fn period_end_date(period: &str) -> Option<String> {
+    let year: i64 = period.get(0..4)?.parse().ok()?;
+    let month: u32 = period.get(5..7)?.parse().ok()?;
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        _ => return None,
+    };
+    Some(format!("{}-{:02}", &period[0..7], days))
+}
+
+/// Sum of the schedule for months 0..=index (accumulated depreciation at the end of that month).
+pub fn accumulated_through(schedule: &[f64], index: i64) -> f64 {
+    if index < 0 {
+        return 0.0;
+    }
+    round2(schedule.iter().take(index as usize + 1).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedules_and_periods() {
+        let sl = monthly_schedule(1000.0, 100.0, 7, "straight_line");
+        assert_eq!(sl.len(), 7);
+        assert_eq!(sl[0], 128.57);
+        assert_eq!(round2(sl.iter().sum()), 900.0);
+
+        let db = monthly_schedule(1200.0, 200.0, 12, "declining_balance");
+        assert_eq!(db[0], 200.0);
+        assert!(db[1] < db[0]);
+        assert_eq!(round2(db.iter().sum()), 1000.0);
+
+        assert!(monthly_schedule(100.0, 100.0, 12, "straight_line").is_empty());
+        assert_eq!(month_index("2024-11-15", "2025-02"), Some(3));
+        assert_eq!(month_index("2024-11-15", "2024-10"), Some(-1));
+        assert_eq!(month_index("bad", "2024-10"), None);
+        assert_eq!(accumulated_through(&sl, 1), 257.14);
+        assert_eq!(accumulated_through(&sl, -1), 0.0);
+        assert_eq!(period_end_date("2024-02").as_deref(), Some("2024-02-29"));
+        assert_eq!(period_end_date("2025-11").as_deref(), Some("2025-11-30"));
+    }
+}
@@ -0,0 +1,178 @@
+//! Language of generated documents: Dari (fa), Pashto (ps) or English (en). Dari and Pashto documents are right to
+//! left, show dates in the solar hijri calendar with the Afghan month names and numbers in Persian digits.
+
+pub const LANGUAGES: [&str; 3] = ["fa", "ps", "en"];
+pub const DEFAULT_LANGUAGE: &str = "fa";
+
+const DARI_MONTHS: [&str; 12] = ["حمل", "ثور", "جوزا", "سرطان", "اسد", "سنبله", "میزان", "عقرب", "قوس", "جدی", "دلو", "حوت"];
+const PASHTO_MONTHS: [&str; 12] = ["وری", "غویی", "غبرګولی", "چنګاښ", "زمری", "وږی", "تله", "لړم", "لیندۍ", "مرغومی", "سلواغه", "کب"];
+const ENGLISH_MONTHS: [&str; 12] =
+    ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"];
+
+/// Labels for template text, as (key, Dari, Pashto, English); templates use them as {{labels.key}}.
+const LABELS: [(&str, &str, &str, &str); 19] = [
+    ("invoice", "فاکتور", "بل", "Invoice"),
+    ("receipt", "رسید", "رسید", "Receipt"),
+    ("reminder", "یادآوری پرداخت", "د تادیې یادونه", "Payment reminder"),
+    ("date", "تاریخ", "نېټه", "Date"),
+    ("customer", "مشتری", "پیرودونکی", "Customer"),
+    ("item", "جنس", "توکی", "Item"),
+    ("quantity", "مقدار", "مقدار", "Qty"),
+    ("price", "قیمت", "بیه", "Price"),
+    ("total", "مجموع", "ټول", "Total"),
+    ("subtotal", "جمع فرعی", "فرعي ټولګه", "Subtotal"),
+    ("discount", "تخفیف", "تخفیف", "Discount"),
+    ("additional_costs", "مصارف اضافی", "اضافي لګښتونه", "Additional costs"),
+    ("paid", "پرداخت شده", "ورکړل شوی", "Paid"),
+    ("remaining", "باقی مانده", "پاتې", "Remaining"),
+    ("balance_due", "مبلغ قابل پرداخت", "د ورکړې وړ پیسې", "Balance due"),
+    ("brought_forward", "نقل از صفحه قبل", "له مخکینۍ پاڼې", "Brought forward"),
+    ("carried_forward", "نقل به صفحه بعد", "بلې پاڼې ته", "Carried forward"),
+    ("page", "صفحه", "پاڼه", "Page"),
+    ("thank_you", "از خرید شما متشکریم", "ستاسو له پیرود څخه مننه", "Thank you"),
+];
+
+/// The language code for a code or name ("dari", "پښتو", "English", ...); None when it is not one we write.
+pub fn normalize(language: &str) -> Option<&'static str> {
+    match language.trim().to_lowercase().as_str() {
+        "fa" | "prs" | "fa-af" | "dari" | "دری" | "persian" | "فارسی" => Some("fa"),
+        "ps" | "pus" | "pashto" | "pushto" | "پښتو" => Some("ps"),
+        "en" | "eng" | "english" => Some("en"),
+        _ => None,
+    }
+}
+
+pub fn is_rtl(language: &str) -> bool {
+    language != "en"
+}
+
+pub fn labels(language: &str) -> Vec<(&'static str, &'static str)> {
+    LABELS
+        .iter()
+        .map(|(key, fa, ps, en)| {
+            let text = match language {
+                "ps" => ps,
+                "en" => en,
+                _ => fa,
+            };
+            (*key, *text)
+        })
+        .collect()
+}
+
+/// Solar hijri (year, month, day) of a gregorian date, by the jalaali break-year algorithm.
+pub fn to_solar_hijri(gy: i64, gm: i64, gd: i64) -> (i64, i64, i64) {
+    const BREAKS: [i64; 20] = [-61, 9, 38, 199, 426, 686, 756, 818, 1111, 1181, 1210, 1635, 2060, 2097, 2192, 2262, 2324, 2394, 2456, 3178];
+    let jy = gy - 621;
+    // Leap state and the March day of Nowruz for year jy
+    let (mut leap_j, mut jp, mut jump) = (-14, BREAKS[0], 0);
+    for jm in BREAKS.iter().skip(1) {
+        jump = jm - jp;
+        if jy < *jm {
+            break;
+        }
+        leap_j += jump / 33 * 8 + (jump % 33) / 4;
+        jp = *jm;
+    }
+    let mut n = jy - jp;
+    leap_j += n / 33 * 8 + ((n % 33) + 3) / 4;
+    if jump % 33 == 4 && jump - n == 4 {
+        leap_j += 1;
+    }
+    let leap_g = gy / 4 - (gy / 100 + 1) * 3 / 4 - 150;
+    let march = 20 + leap_j - leap_g;
+    if jump - n < 6 {
+        n = n - jump + (jump + 4) / 33 * 33;
+    }
+    let mut leap = (((n + 1) % 33) - 1) % 4;
+    if leap == -1 {
+        leap = 4;
+    }
+
+    let day_number = |y: i64, m: i64, d: i64| {
+        let mut jdn = (y + (m - 8) / 6 + 100100) * 1461 / 4 + (153 * ((m + 9) % 12) + 2) / 5 + d - 34840408;
+        jdn -= (y + 100100 + (m - 8) / 6) / 100 * 3 / 4 - 752;
+        jdn
+    };
+    let mut k = day_number(gy, gm, gd) - day_number(gy, 3, march);
+    let mut jy = jy;
+    if k >= 0 {
+        if k <= 185 {
+            return (jy, 1 + k / 31, k % 31 + 1);
+        }
+        k -= 186;
+    } else {
+        jy -= 1;
+        k += 179;
+        if leap == 1 {
+            k += 1;
+        }
+    }
+    (jy, 7 + k / 30, k % 30 + 1)
+}
+
+/// A "YYYY-MM-DD..." date as written in `language`: "12 دلو 1402" for Dari and Pashto (digits are converted with
+/// localize_number), "1 February 2024" for English. Text that is not a date is returned unchanged.
+pub fn format_date(date: &str, language: &str) -> String {
+    let parts: Vec<i64> = date.get(..10).unwrap_or("").split('-').filter_map(|p| p.parse().ok()).collect();
+    let [y, m, d] = parts[..] else {
+        return date.to_string();
+    };
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return date.to_string();
+    }
+    match language {
+        "en" => format!("{} {} {}", d, ENGLISH_MONTHS[(m - 1) as usize], y),
+        _ => {
+            let (jy, jm, jd) = to_solar_hijri(y, m, d);
+            let months = if language == "ps" { &PASHTO_MONTHS } else { &DARI_MONTHS };
+            format!("{} {} {}", jd, months[(jm - 1) as usize], jy)
+        }
+    }
+}
+
+/// Number text in the language's digits: Persian digits and separators (٬ thousands, ٫ decimal) for Dari and
+/// Pashto. Commas and dots count as separators only between digits.
+pub fn localize_number(text: &str, language: &str) -> String {
+    if !is_rtl(language) {
+        return text.to_string();
+    }
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let between_digits = i > 0 && chars[i - 1].is_ascii_digit() && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit());
+            match c {
+                '0'..='9' => char::from_u32(0x06F0 + (*c as u32 - '0' as u32)).unwrap_or(*c),
+                ',' if between_digits => '٬',
+                '.' if between_digits => '٫',
+                _ => *c,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_locale() {
+        assert_eq!(to_solar_hijri(2024, 3, 20), (1403, 1, 1));
+        assert_eq!(to_solar_hijri(2024, 3, 19), (1402, 12, 29));
+        assert_eq!(to_solar_hijri(2024, 2, 1), (1402, 11, 12));
+        assert_eq!(to_solar_hijri(2023, 9, 23), (1402, 7, 1));
+        assert_eq!(format_date("2024-02-01", "fa"), "12 دلو 1402");
+        assert_eq!(format_date("2024-02-01 10:30:00", "ps"), "12 سلواغه 1402");
+        assert_eq!(format_date("2024-02-01", "en"), "1 February 2024");
+        assert_eq!(format_date("pending", "fa"), "pending");
+        assert_eq!(localize_number("1,234.50 ؋", "fa"), "۱٬۲۳۴٫۵۰ ؋");
+        assert_eq!(localize_number("Rs. 12", "ps"), "Rs. ۱۲");
+        assert_eq!(localize_number("1,234.50", "en"), "1,234.50");
+        assert_eq!(normalize("Dari"), Some("fa"));
+        assert_eq!(normalize("پښتو"), Some("ps"));
+        assert_eq!(normalize("fr"), None);
+        assert_eq!(labels("ps").iter().find(|(k, _)| *k == "date").map(|l| l.1), Some("نېټه"));
+    }
+}
@@ -0,0 +1,208 @@
+//! User-editable document templates with handlebars-like placeholders.
+//!
+//! Supported syntax: `{{path.to.value}}` (HTML-escaped when rendering for HTML), `{{{raw}}}`,
+//! `{{#each list}}…{{/each}}` (inside: fields of the item, `{{this}}`, `{{@index}}` from 0 and `{{@number}}` from 1),
+//! `{{#if value}}…{{else}}…{{/if}}` and `{{#unless value}}…{{/unless}}`. Lookups inside a block fall back to the
+//! outer scopes, so `{{company.name}}` works inside `{{#each lines}}`.
+
+use serde_json::Value;
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Var { path: String, raw: bool },
+    Each { path: String, body: Vec<Node> },
+    If { path: String, negate: bool, then: Vec<Node>, otherwise: Vec<Node> },
+}
+
+enum Tag {
+    Var(String, bool),
+    Open(String, String),
+    Else,
+    Close(String),
+}
+
+fn parse_tag(inner: &str, raw: bool) -> Result<Tag, String> {
+    let inner = inner.trim();
+    if raw {
+        return Ok(Tag::Var(inner.to_string(), true));
+    }
+    if let Some(rest) = inner.strip_prefix('#') {
+        let (kind, arg) = rest.split_once(char::is_whitespace).ok_or_else(|| format!("Block needs an argument: {{{{{}}}}}", inner))?;
+        return match kind {
+            "each" | "if" | "unless" => Ok(Tag::Open(kind.to_string(), arg.trim().to_string())),
+            _ => Err(format!("Unknown block: {}", kind)),
+        };
+    }
+    if let Some(kind) = inner.strip_prefix('/') {
+        return Ok(Tag::Close(kind.trim().to_string()));
+    }
+    if inner == "else" {
+        return Ok(Tag::Else);
+    }
+    Ok(Tag::Var(inner.to_string(), false))
+}
+
+/// Parse nodes until a closing tag of `until` (or end of input when `until` is None).
+/// Returns the nodes and, for if/unless, the nodes after `{{else}}`.
+fn parse_nodes(src: &str, pos: &mut usize, until: Option<&str>) -> Result<(Vec<Node>, Vec<Node>), String> {
+    let mut nodes = Vec::new();
+    let mut else_nodes = Vec::new();
+    let mut in_else = false;
+    while *pos < src.len() {
+        let rest = &src[*pos..];
+        let Some(start) = rest.find("{{") else {
+            push_text(if in_else { &mut else_nodes } else { &mut nodes }, rest);
+            *pos = src.len();
+            break;
+        };
+        push_text(if in_else { &mut else_nodes } else { &mut nodes }, &rest[..start]);
+        let raw = rest[start..].starts_with("{{{");
+        let (open_len, close) = if raw { (3, "}}}") } else { (2, "}}") };
+        let after_open = &rest[start + open_len..];
+        let end = after_open.find(close).ok_or("Unclosed {{ in template")?;
+        let tag = parse_tag(&after_open[..end], raw)?;
+        *pos += start + open_len + end + close.len();
+        let target = if in_else { &mut else_nodes } else { &mut nodes };
+        match tag {
+            Tag::Var(path, raw) => target.push(Node::Var { path, raw }),
+            Tag::Open(kind, path) => {
+                let (body, otherwise) = parse_nodes(src, pos, Some(&kind))?;
+                target.push(match kind.as_str() {
+                    "each" => Node::Each { path, body },
+                    _ => Node::If { path, negate: kind == "unless", then: body, otherwise },
+                });
+            }
+            Tag::Else => {
+                if !matches!(until, Some("if") | Some("unless")) || in_else {
+                    return Err("{{else}} outside of {{#if}}".to_string());
+                }
+                in_else = true;
+            }
+            Tag::Close(kind) => {
+                if until == Some(kind.as_str()) {
+                    return Ok((nodes, else_nodes));
+                }
+                return Err(format!("Unexpected {{{{/{}}}}}", kind));
+            }
+        }
+    }
+    match until {
+        Some(kind) => Err(format!("Missing {{{{/{}}}}}", kind)),
+        None => Ok((nodes, else_nodes)),
+    }
+}
+
+fn push_text(nodes: &mut Vec<Node>, text: &str) {
+    if !text.is_empty() {
+        nodes.push(Node::Text(text.to_string()));
+    }
+}
+
+fn lookup<'a>(scopes: &[&'a Value], path: &str) -> Option<&'a Value> {
+    let current = *scopes.last()?;
+    if path == "this" || path == "." {
+        return Some(current);
+    }
+    let path = path.strip_prefix("this.").unwrap_or(path);
+    scopes.iter().rev().find_map(|scope| {
+        path.split('.').try_fold(*scope, |v, key| match v {
+            Value::Object(map) => map.get(key),
+            Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+    })
+}
+
+fn truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => false,
+        Some(Value::Bool(b)) => *b,
+        Some(Value::Number(n)) => n.as_f64().is_some_and(|f| f != 0.0),
+        Some(Value::String(s)) => !s.is_empty(),
+        Some(Value::Array(a)) => !a.is_empty(),
+        Some(Value::Object(_)) => true,
+    }
+}
+
+fn display(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => match n.as_i64() {
+            Some(i) => i.to_string(),
+            None => format!("{:.2}", n.as_f64().unwrap_or(0.0)),
+        },
+        Some(other) => other.to_string(),
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn render_nodes(nodes: &[Node], scopes: &[&Value], html: bool, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(t) => out.push_str(t),
+            Node::Var { path, raw } => {
+                let text = display(lookup(scopes, path));
+                out.push_str(&if html && !raw { escape_html(&text) } else { text });
+            }
+            Node::If { path, negate, then, otherwise } => {
+                let branch = if truthy(lookup(scopes, path)) != *negate { then } else { otherwise };
+                render_nodes(branch, scopes, html, out);
+            }
+            Node::Each { path, body } => {
+                let Some(Value::Array(items)) = lookup(scopes, path) else { continue };
+                for (i, item) in items.iter().enumerate() {
+                    let mut with_index = item.clone();
+                    if let Value::Object(map) = &mut with_index {
+                        map.insert("@index".to_string(), Value::from(i));
+                        map.insert("@number".to_string(), Value::from(i + 1));
+                    }
+                    let mut inner = scopes.to_vec();
+                    inner.push(&with_index);
+                    render_nodes(body, &inner, html, out);
+                }
+            }
+        }
+    }
+}
+
+/// Check a template's syntax without rendering it.
+pub fn validate(template: &str) -> Result<(), String> {
+    parse_nodes(template, &mut 0, None).map(|_| ())
+}
+
+/// Render `template` against `context`. With `html` set, `{{value}}` output is HTML-escaped (use `{{{value}}}` for raw).
+pub fn render(template: &str, context: &Value, html: bool) -> Result<String, String> {
+    let (nodes, _) = parse_nodes(template, &mut 0, None)?;
+    let mut out = String::new();
+    render_nodes(&nodes, &[context], html, &mut out);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render() {
+        let ctx = json!({
+            "company": { "name": "A & B" },
+            "customer": { "name": "Ali" },
+            "lines": [{ "name": "Tea", "total": 12.5 }, { "name": "Rice", "total": 3 }],
+            "notes": "",
+        });
+        let tpl = "{{company.name}}|{{#each lines}}{{@number}}.{{name}}={{total}} {{customer.name}};{{/each}}|{{#if notes}}N{{else}}-{{/if}}{{#unless notes}}U{{/unless}}";
+        assert_eq!(render(tpl, &ctx, false).unwrap(), "A & B|1.Tea=12.50 Ali;2.Rice=3 Ali;|-U");
+        assert_eq!(render("{{company.name}} {{{company.name}}}", &ctx, true).unwrap(), "A &amp; B A & B");
+        assert_eq!(render("{{missing.value}}x", &ctx, false).unwrap(), "x");
+        assert!(validate("{{#each lines}}").is_err());
+        assert!(validate("{{#if a}}{{/each}}").is_err());
+        assert!(validate("{{else}}").is_err());
+        assert!(validate("{{#each lines}}{{#if name}}{{name}}{{/if}}{{/each}}").is_ok());
+    }
+}
@@ -0,0 +1,690 @@
+//! End-to-end tests of the money-critical flows against a real MySQL server, built with `--features e2e`.
+//! Each test gets its own database on the server configured by the MYSQL_* variables (point them at a disposable
+//! test server, e.g. a container), created from the embedded schema and dropped when the test ends.
+
+use super::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri::test::{mock_app, MockRuntime};
+
+/// Backups and restores read MYSQL_DATABASE, so the tests run one at a time.
+static SERIAL: Mutex<()> = Mutex::new(());
+static NEXT_DATABASE: AtomicUsize = AtomicUsize::new(0);
+
+/// Connection to the test server without a default database.
+fn server_conn() -> mysql::Conn {
+    let opts = OptsBuilder::from_opts(get_mysql_opts().expect("MYSQL_* settings")).db_name(None::<String>);
+    mysql::Conn::new(opts).expect("connect to the test MySQL server")
+}
+
+/// An ephemeral database with the full schema, and a mock app holding it and an admin session the way the real
+/// app manages them, so commands are called exactly as the UI calls them.
+struct TestDb {
+    name: String,
+    app: tauri::App<MockRuntime>,
+    unit_id: i64,
+    supplier_id: i64,
+    _serial: std::sync::MutexGuard<'static, ()>,
+}
+
+impl TestDb {
+    fn new() -> Self {
+        let serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let name = format!("shafaf_e2e_{}_{}", std::process::id(), NEXT_DATABASE.fetch_add(1, Ordering::SeqCst));
+        server_conn().query_drop(format!("CREATE DATABASE `{}`", name)).expect("create test database");
+        std::env::set_var("MYSQL_DATABASE", &name);
+
+        let db = Database::new(get_mysql_opts().unwrap());
+        db.open().expect("open test database");
+        for stmt in schema::statements(INIT_SQL) {
+            db.execute(&stmt, ()).unwrap_or_else(|e| panic!("schema statement failed: {} | {}", e, stmt));
+        }
+        insert_test_user_if_needed(&db).unwrap();
+        let user_id = db.query("SELECT id FROM users WHERE username = 'testuser'", (), |row| Ok(row_get::<i64>(row, 0)?)).unwrap()[0];
+        let unit_id = db.query("SELECT id FROM units WHERE ratio = 1 ORDER BY id LIMIT 1", (), |row| Ok(row_get::<i64>(row, 0)?)).unwrap()[0];
+        let supplier_id = db
+            .insert("INSERT INTO suppliers (full_name, phone, address) VALUES ('Test Supplier', '0700000000', 'Kabul')", ())
+            .unwrap();
+
+        let app = mock_app();
+        app.manage(RwLock::new(Some(db)));
+        app.manage(Mutex::new(Some(SessionUser {
+            id: user_id,
+            username: "testuser".to_string(),
+            role: "admin".to_string(),
+            token: String::new(),
+            permissions: Vec::new(),
+            locked: false,
+            auto_lock_minutes: 0,
+            last_active: None,
+        })));
+        TestDb { name, app, unit_id, supplier_id, _serial: serial }
+    }
+
+    fn db_state(&self) -> State<'_, RwLock<Option<Database>>> {
+        self.app.state()
+    }
+
+    fn session(&self) -> State<'_, Mutex<Option<SessionUser>>> {
+        self.app.state()
+    }
+
+    fn with_db<R>(&self, f: impl FnOnce(&Database) -> R) -> R {
+        let guard = self.db_state().inner().read().unwrap();
+        f(guard.as_ref().unwrap())
+    }
+
+    /// First column of the first row (a DOUBLE or DECIMAL column).
+    fn scalar(&self, sql: &str, params: Vec<Value>) -> f64 {
+        self.with_db(|db| db.query(sql, params, |row| Ok(row_get::<f64>(row, 0)?)).unwrap().first().copied().unwrap_or(0.0))
+    }
+
+    fn count(&self, sql: &str) -> i64 {
+        self.with_db(|db| db.query(sql, (), |row| Ok(row_get::<i64>(row, 0)?)).unwrap().first().copied().unwrap_or(0))
+    }
+
+    fn product(&self, name: &str) -> ProductBuilder<'_> {
+        ProductBuilder { t: self, name: name.to_string(), price: 0.0, batches: Vec::new() }
+    }
+
+    fn customer(&self, name: &str) -> i64 {
+        self.with_db(|db| {
+            db.insert("INSERT INTO customers (full_name, phone, address) VALUES (?, '0790000000', 'Kabul')", one_param(name)).unwrap()
+        })
+    }
+
+    fn sale(&self, customer_id: i64) -> SaleBuilder<'_> {
+        SaleBuilder { t: self, customer_id, date: "2024-02-01".to_string(), items: Vec::new(), paid: None, warehouse: None }
+    }
+
+    fn batches(&self, product_id: i64) -> Vec<i64> {
+        self.with_db(|db| {
+            db.query(
+                "SELECT pi.id FROM purchase_items pi INNER JOIN purchases p ON p.id = pi.purchase_id WHERE pi.product_id = ? ORDER BY p.date, pi.id",
+                one_param(product_id),
+                |row| Ok(row_get::<i64>(row, 0)?),
+            )
+            .unwrap()
+        })
+    }
+
+    fn batch_remaining(&self, purchase_item_id: i64) -> f64 {
+        self.with_db(|db| get_batch_remaining_base(db, purchase_item_id).unwrap())
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let _ = server_conn().query_drop(format!("DROP DATABASE IF EXISTS `{}`", self.name));
+    }
+}
+
+/// A product and the batches it is bought in; each batch is a separate purchase, dated a day apart.
+struct ProductBuilder<'a> {
+    t: &'a TestDb,
+    name: String,
+    price: f64,
+    batches: Vec<(f64, f64)>,
+}
+
+impl ProductBuilder<'_> {
+    fn price(mut self, price: f64) -> Self {
+        self.price = price;
+        self
+    }
+
+    /// A batch of `amount` base units bought at `cost` each.
+    fn batch(mut self, amount: f64, cost: f64) -> Self {
+        self.batches.push((amount, cost));
+        self
+    }
+
+    fn create(self) -> i64 {
+        let t = self.t;
+        let product_id = t.with_db(|db| {
+            db.insert("INSERT INTO products (name, price, unit) VALUES (?, ?, 'piece')", (self.name.as_str(), self.price)).unwrap()
+        });
+        for (i, (amount, cost)) in self.batches.into_iter().enumerate() {
+            create_purchase(
+                t.db_state(),
+                t.session(),
+                t.supplier_id,
+                format!("2024-01-{:02}", i + 1),
+                None,
+                None,
+                Vec::new(),
+                vec![(product_id, t.unit_id, cost, amount, None, None, None, None, None)],
+                None,
+                None,
+            )
+            .expect("purchase batch");
+        }
+        product_id
+    }
+}
+
+/// A sale in the base currency; it is paid in full unless `paid` says otherwise.
+struct SaleBuilder<'a> {
+    t: &'a TestDb,
+    customer_id: i64,
+    date: String,
+    items: Vec<(i64, i64, f64, f64, Option<i64>, Option<String>, Option<String>, f64)>,
+    paid: Option<f64>,
+    warehouse: Option<i64>,
+}
+
+impl SaleBuilder<'_> {
+    fn item(mut self, product_id: i64, batch_id: i64, amount: f64, price: f64) -> Self {
+        self.items.push((product_id, self.t.unit_id, price, amount, Some(batch_id), Some("retail".to_string()), None, 0.0));
+        self
+    }
+
+    fn paid(mut self, amount: f64) -> Self {
+        self.paid = Some(amount);
+        self
+    }
+
+    fn from_warehouse(mut self, warehouse_id: i64) -> Self {
+        self.warehouse = Some(warehouse_id);
+        self
+    }
+
+    fn create(self) -> Result<Sale, String> {
+        let total: f64 = self.items.iter().map(|i| i.2 * i.3).sum();
+        create_sale(
+            self.t.db_state(),
+            self.t.session(),
+            self.customer_id,
+            self.date,
+            None,
+            None,
+            1.0,
+            self.paid.unwrap_or(total),
+            Vec::new(),
+            self.items,
+            Vec::new(),
+            None,
+            0.0,
+            None,
+            None,
+            None,
+            None,
+            None,
+            self.warehouse,
+        )
+    }
+}
+
+#[test]
+fn sale_draws_down_the_chosen_batch() {
+    let t = TestDb::new();
+    let product = t.product("Rice 5kg").price(60.0).batch(10.0, 40.0).batch(5.0, 45.0).create();
+    let customer = t.customer("Ahmad");
+    let batches = t.batches(product);
+    assert_eq!(batches.len(), 2);
+
+    t.sale(customer).item(product, batches[0], 4.0, 60.0).create().unwrap();
+    assert_eq!(t.batch_remaining(batches[0]), 6.0);
+    assert_eq!(t.batch_remaining(batches[1]), 5.0);
+    assert_eq!(t.with_db(|db| product_stock_base(db, product).unwrap()), 11.0);
+
+    // Overselling a batch is refused and writes nothing
+    assert!(t.sale(customer).item(product, batches[0], 7.0, 60.0).create().is_err());
+    assert_eq!(t.count("SELECT COUNT(*) FROM sales"), 1);
+    assert_eq!(t.batch_remaining(batches[0]), 6.0);
+}
+
+#[test]
+fn payments_settle_a_credit_sale() {
+    let t = TestDb::new();
+    let product = t.product("Oil 1L").price(100.0).batch(20.0, 70.0).create();
+    let customer = t.customer("Karim");
+    let batch = t.batches(product)[0];
+
+    let sale = t.sale(customer).item(product, batch, 1.0, 100.0).paid(40.0).create().unwrap();
+    assert_eq!(sale.total_amount, 100.0);
+    assert_eq!(sale.paid_amount, 40.0);
+
+    create_sale_payment(t.db_state(), sale.id, None, None, 1.0, 60.0, "2024-02-05".to_string()).unwrap();
+    assert_eq!(t.scalar("SELECT paid_amount FROM sales WHERE id = ?", one_param(sale.id)), 100.0);
+    assert_eq!(get_sale_payments(t.db_state(), t.session(), sale.id).unwrap().len(), 2);
+
+    // Sale and payments post balanced journal entries and leave no batch oversold
+    assert_eq!(t.with_db(verify_integrity).unwrap(), Vec::<String>::new());
+}
+
+#[test]
+fn long_invoice_totals_are_penny_exact() {
+    let t = TestDb::new();
+    let product = t.product("Pencil").price(0.2).batch(1000.0, 0.05).create();
+    let customer = t.customer("Farid");
+    let batch = t.batches(product)[0];
+
+    // 1000 lines of 0.10 and 0.20: adding them as f64 misses 150 by a fraction of a cent
+    let sale = (0..1000)
+        .fold(t.sale(customer), |sale, i| sale.item(product, batch, 1.0, if i % 2 == 0 { 0.1 } else { 0.2 }))
+        .paid(150.0)
+        .create()
+        .unwrap();
+    assert_eq!(sale.total_amount, 150.0);
+    assert_eq!(t.scalar("SELECT total_amount FROM sales WHERE id = ?", one_param(sale.id)), 150.0);
+    assert_eq!(t.scalar("SELECT SUM(total) FROM sale_items WHERE sale_id = ?", one_param(sale.id)), 150.0);
+    assert_eq!(t.scalar("SELECT total_amount FROM purchases WHERE supplier_id = ?", one_param(t.supplier_id)), 50.0);
+
+    // DECIMAL columns reach JSON consumers (db_query, REST, GraphQL) as numbers
+    let rows = t.with_db(|db| query_json_objects(db, "SELECT total_amount FROM sales WHERE id = ?", vec![Value::from(sale.id)])).unwrap();
+    assert_eq!(rows[0]["total_amount"], serde_json::json!(150.0));
+}
+
+#[test]
+fn money_columns_of_older_databases_become_decimal() {
+    let t = TestDb::new();
+    let column_type = |table: &str, column: &str| {
+        t.with_db(|db| {
+            db.query(
+                "SELECT data_type FROM information_schema.columns WHERE table_schema = DATABASE() AND table_name = ? AND column_name = ?",
+                (table, column),
+                |row| Ok(row_get::<String>(row, 0)?),
+            )
+            .unwrap()
+            .remove(0)
+        })
+    };
+    let money = [("products", "price"), ("sale_items", "per_price"), ("product_deposits", "amount"), ("shipping_rules", "min_charge"), ("sale_bundles", "price")];
+    t.with_db(|db| {
+        for (table, column) in money {
+            db.execute(&format!("ALTER TABLE {} MODIFY COLUMN {} DOUBLE NOT NULL DEFAULT 0", table, column), ()).unwrap();
+        }
+        migrate_money_columns(db).unwrap();
+    });
+    for (table, column) in money {
+        assert_eq!(column_type(table, column), "decimal", "{}.{}", table, column);
+    }
+}
+
+#[test]
+fn deleting_a_sale_returns_its_stock() {
+    let t = TestDb::new();
+    let product = t.product("Sugar 1kg").price(50.0).batch(8.0, 35.0).create();
+    let customer = t.customer("Mahmood");
+    let batch = t.batches(product)[0];
+
+    let sale = t.sale(customer).item(product, batch, 3.0, 50.0).create().unwrap();
+    assert_eq!(t.batch_remaining(batch), 5.0);
+    delete_sale(t.db_state(), sale.id).unwrap();
+    assert_eq!(t.batch_remaining(batch), 8.0);
+    assert_eq!(t.count("SELECT COUNT(*) FROM sale_items"), 0);
+}
+
+#[test]
+fn backup_restore_round_trip() {
+    let t = TestDb::new();
+    let product = t.product("Tea 500g").price(120.0).batch(12.0, 90.0).create();
+    let backup = std::env::temp_dir().join(format!("{}.sql", t.name));
+    dump_database_to(&backup).expect("mysqldump");
+
+    t.with_db(|db| db.execute("UPDATE products SET price = 0 WHERE id = ?", one_param(product)).unwrap());
+
+    restore_database(backup.to_string_lossy().to_string()).expect("restore");
+    let _ = fs::remove_file(&backup);
+    assert_eq!(t.scalar("SELECT price FROM products WHERE id = ?", one_param(product)), 120.0);
+    assert_eq!(t.with_db(|db| product_stock_base(db, product).unwrap()), 12.0);
+    // Users are kept as they are on restore
+    assert_eq!(t.count("SELECT COUNT(*) FROM users WHERE username = 'testuser'"), 1);
+}
+
+#[test]
+fn failed_atomic_block_writes_nothing() {
+    let t = TestDb::new();
+    let customer = t.customer("Nasir");
+    let result: Result<(), String> = t.with_db(|db| {
+        db.atomic(|| {
+            db.execute("UPDATE customers SET address = 'Herat' WHERE id = ?", one_param(customer)).unwrap();
+            // Nested blocks join the outer transaction
+            db.atomic(|| create_journal_entry_internal(db, "2024-02-01", None, None, None, Vec::new()))?;
+            Err("later step failed".to_string())
+        })
+    });
+    assert!(result.is_err());
+    assert_eq!(t.count("SELECT COUNT(*) FROM journal_entries"), 0);
+    assert_eq!(t.count("SELECT COUNT(*) FROM customers WHERE address = 'Herat'"), 0);
+}
+
+#[test]
+fn sale_return_restocks_and_credits_the_customer() {
+    let t = TestDb::new();
+    let product = t.product("Tea 500g").price(50.0).batch(10.0, 30.0).create();
+    let customer = t.customer("Nasir");
+    let batch = t.batches(product)[0];
+
+    let sale = t.sale(customer).item(product, batch, 5.0, 50.0).paid(100.0).create().unwrap();
+    let sale_item = t.scalar("SELECT id FROM sale_items WHERE sale_id = ?", one_param(sale.id)) as i64;
+    let items = vec![(sale_item, t.unit_id, 2.0)];
+    let (credit_note, lines) =
+        create_sale_return(t.db_state(), t.session(), sale.id, sale.date.clone(), Some("Damaged".to_string()), items).unwrap();
+    assert_eq!(credit_note.total_amount, 100.0);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(t.batch_remaining(batch), 7.0);
+    assert_eq!(t.scalar("SELECT paid_amount FROM sales WHERE id = ?", one_param(sale.id)), 200.0);
+    assert_eq!(t.with_db(|db| customer_balance_after(db, customer, 0.0).unwrap()), 50.0);
+
+    // No more than what is left of the line can come back
+    let too_many = vec![(sale_item, t.unit_id, 4.0)];
+    assert!(create_sale_return(t.db_state(), t.session(), sale.id, sale.date.clone(), None, too_many).is_err());
+    assert_eq!(get_sale_returns(t.db_state(), t.session(), 1, 10, None, Some(customer), None).unwrap().total, 1);
+    assert_eq!(t.with_db(verify_integrity).unwrap(), Vec::<String>::new());
+
+    delete_sale_return(t.db_state(), credit_note.id).unwrap();
+    assert_eq!(t.batch_remaining(batch), 5.0);
+    assert_eq!(t.scalar("SELECT paid_amount FROM sales WHERE id = ?", one_param(sale.id)), 100.0);
+}
+
+#[test]
+fn fefo_sale_takes_the_earliest_expiry_and_skips_expired_batches() {
+    let t = TestDb::new();
+    let product = t.product("Yogurt 1kg").price(20.0).batch(10.0, 12.0).batch(10.0, 12.0).batch(5.0, 12.0).create();
+    let customer = t.customer("Farid");
+    let batches = t.batches(product);
+    t.with_db(|db| {
+        for (batch, expiry) in batches.iter().zip(["2099-12-31", "2000-01-01", "2098-06-30"]) {
+            db.execute("UPDATE purchase_items SET expiry_date = ? WHERE id = ?", (expiry, batch)).unwrap();
+        }
+    });
+
+    let items = vec![(product, t.unit_id, 20.0, 8.0, None, None, Some("fixed".to_string()), 8.0)];
+    let sale = create_sale(
+        t.db_state(),
+        t.session(),
+        customer,
+        "2024-02-01".to_string(),
+        None,
+        None,
+        1.0,
+        152.0,
+        Vec::new(),
+        items,
+        Vec::new(),
+        None,
+        0.0,
+        None,
+        None,
+        None,
+        None,
+        Some("fefo".to_string()),
+        None,
+    )
+    .unwrap();
+    assert_eq!(sale.total_amount, 152.0);
+    assert_eq!(t.batch_remaining(batches[2]), 0.0);
+    assert_eq!(t.batch_remaining(batches[0]), 7.0);
+    assert_eq!(t.batch_remaining(batches[1]), 10.0);
+
+    // The product's own strategy applies to stock picked without one for the sale
+    set_product_batch_strategy(t.db_state(), product, Some("fifo".to_string())).unwrap();
+    let picks = t.with_db(|db| allocate_batches(db, product, 12.0, None, &HashMap::new(), None).unwrap());
+    assert_eq!(picks, vec![(batches[0], 7.0), (batches[1], 5.0)]);
+}
+
+#[test]
+fn stock_transfer_moves_batch_stock_between_warehouses() {
+    let t = TestDb::new();
+    let product = t.product("Oil 1L").price(120.0).batch(10.0, 90.0).create();
+    let customer = t.customer("Farid");
+    let batch = t.batches(product)[0];
+    let main = t.with_db(|db| default_warehouse_id(db)).unwrap();
+    let branch = create_warehouse(t.db_state(), "Shop".to_string(), None, None).unwrap().id;
+    let stock_at = |warehouse: i64| t.with_db(|db| batch_stock_in_warehouse(db, batch, warehouse).unwrap());
+
+    let transfer =
+        create_stock_transfer(t.db_state(), t.session(), "2024-02-01".to_string(), main, branch, None, vec![(batch, t.unit_id, 4.0)]).unwrap();
+    assert_eq!(transfer.items.len(), 1);
+    assert_eq!((stock_at(main), stock_at(branch)), (6.0, 4.0));
+    assert_eq!(t.batch_remaining(batch), 10.0);
+    let shop_stock = get_warehouse_stock(t.db_state(), Some(branch), None).unwrap();
+    assert_eq!(shop_stock.len(), 1);
+    assert_eq!(shop_stock[0].remaining_quantity, 4.0);
+
+    // A warehouse sells only what it holds
+    assert!(t.sale(customer).item(product, batch, 5.0, 120.0).from_warehouse(branch).create().is_err());
+    t.sale(customer).item(product, batch, 3.0, 120.0).from_warehouse(branch).create().unwrap();
+    assert_eq!((stock_at(main), stock_at(branch)), (6.0, 1.0));
+    assert_eq!(t.batch_remaining(batch), 7.0);
+
+    // Nor can it send more than it holds
+    let too_many = vec![(batch, t.unit_id, 2.0)];
+    assert!(create_stock_transfer(t.db_state(), t.session(), "2024-02-02".to_string(), branch, main, None, too_many).is_err());
+    assert_eq!(get_stock_transfers(t.db_state(), Some(branch)).unwrap().len(), 1);
+}
+
+#[test]
+fn sales_take_consecutive_invoice_numbers() {
+    let t = TestDb::new();
+    let product = t.product("Sugar 1kg").price(80.0).batch(10.0, 60.0).create();
+    let customer = t.customer("Zahra");
+    let batch = t.batches(product)[0];
+
+    let preview = get_next_invoice_number(t.db_state(), Some("2024-02-01".to_string())).unwrap();
+    let first = t.sale(customer).item(product, batch, 1.0, 80.0).create().unwrap();
+    let second = t.sale(customer).item(product, batch, 1.0, 80.0).create().unwrap();
+    assert_eq!(first.invoice_number.as_deref(), Some(preview.as_str()));
+    assert_eq!(first.invoice_number.as_deref(), Some("INV-2024-000001"));
+    assert_eq!(second.invoice_number.as_deref(), Some("INV-2024-000002"));
+
+    // A refused sale does not use up a number
+    assert!(t.sale(customer).item(product, batch, 50.0, 80.0).create().is_err());
+    set_invoice_number_scheme(t.db_state(), "S".to_string(), 3, false, None).unwrap();
+    let third = t.sale(customer).item(product, batch, 1.0, 80.0).create().unwrap();
+    assert_eq!(third.invoice_number.as_deref(), Some("S001"));
+}
+
+#[test]
+fn finalized_period_detects_later_changes() {
+    let t = TestDb::new();
+    let product = t.product("Flour 5kg").price(300.0).batch(10.0, 250.0).create();
+    let customer = t.customer("Nadia");
+    let batch = t.batches(product)[0];
+    t.with_db(|db| db.insert("INSERT INTO journal_entries (entry_number, entry_date, description) VALUES ('JE-T1', '2024-02-01', 'Opening cash')", ()).unwrap());
+
+    let signed = finalize_period(t.db_state(), t.session(), "2024-02".to_string(), Some("Auditor".to_string()), None, None).unwrap();
+    assert_eq!(signed.tables.iter().find(|d| d.table == "journal_entries").map(|d| d.rows), Some(1));
+    assert!(verify_period(t.db_state(), "2024-02".to_string()).unwrap().intact);
+
+    // The finalized dates are locked
+    assert!(t.sale(customer).item(product, batch, 1.0, 300.0).create().is_err());
+
+    t.with_db(|db| db.execute("UPDATE journal_entries SET description = 'Edited' WHERE entry_number = 'JE-T1'", ()).unwrap());
+    let check = verify_period(t.db_state(), "2024-02".to_string()).unwrap();
+    assert!(!check.intact);
+    assert_eq!(check.changes, vec!["journal_entries: rows changed".to_string()]);
+
+    reopen_period(t.db_state(), t.session(), "2024-02".to_string(), "Correct the opening entry".to_string()).unwrap();
+    t.sale(customer).item(product, batch, 1.0, 300.0).create().unwrap();
+}
+
+#[test]
+fn layered_settings_resolve_most_specific_scope() {
+    let t = TestDb::new();
+    let branch = create_warehouse(t.db_state(), "Shop".to_string(), None, None).unwrap().id;
+    let set = |scope: &str, id: Option<String>, value: Option<&str>| {
+        set_scoped_setting(t.db_state(), "price_level".to_string(), scope.to_string(), id, value.map(str::to_string))
+    };
+    set("global", None, Some("retail")).unwrap();
+    set("warehouse", Some(branch.to_string()), Some("wholesale")).unwrap();
+    set("terminal", Some("Counter 2".to_string()), Some("retail")).unwrap();
+    assert!(set("global", None, Some("vip")).is_err());
+
+    let price_level = |context: SettingsContext| {
+        let settings = get_effective_settings(t.db_state(), t.session(), Some(context)).unwrap();
+        let s = settings.into_iter().find(|s| s.key == "price_level").unwrap();
+        (s.value.unwrap(), s.source.unwrap())
+    };
+    let at_branch = SettingsContext { warehouse_id: Some(branch), terminal: Some("Counter 1".to_string()), user_id: None };
+    assert_eq!(price_level(at_branch.clone()), ("wholesale".to_string(), "warehouse".to_string()));
+    assert_eq!(price_level(SettingsContext { terminal: Some("Counter 2".to_string()), ..at_branch.clone() }), ("retail".to_string(), "terminal".to_string()));
+    assert_eq!(price_level(SettingsContext { warehouse_id: None, ..at_branch.clone() }), ("retail".to_string(), "global".to_string()));
+
+    // Removing the warehouse value falls back to the global one
+    set("warehouse", Some(branch.to_string()), None).unwrap();
+    assert_eq!(price_level(at_branch), ("retail".to_string(), "global".to_string()));
+
+    // Batches are priced at the level of this terminal's warehouse and the logged-in user
+    let product = t.product("Tea 1kg").price(100.0).batch(10.0, 60.0).create();
+    t.with_db(|db| db.execute("UPDATE purchase_items SET wholesale_price = 80, retail_price = 100 WHERE product_id = ?", one_param(product)).unwrap());
+    let sale_price = || get_product_batches(t.db_state(), t.session(), product).unwrap()[0].sale_price;
+    assert_eq!(sale_price(), Some(100.0));
+    let user = t.session().lock().unwrap().as_ref().unwrap().id;
+    set("user", Some(user.to_string()), Some("wholesale")).unwrap();
+    assert_eq!(sale_price(), Some(80.0));
+}
+
+#[test]
+fn merging_customers_moves_every_reference() {
+    let t = TestDb::new();
+    let product = t.product("Rice").price(100.0).batch(10.0, 60.0).create();
+    let batch = t.batches(product)[0];
+    let keep = t.customer("Ahmad");
+    let duplicate = t.customer("Ahmad K.");
+    t.sale(duplicate).item(product, batch, 1.0, 100.0).create().unwrap();
+    t.with_db(|db| {
+        db.execute("INSERT INTO customer_tags (customer_id, tag) VALUES (?, 'vip'), (?, 'vip'), (?, 'wholesale')", (keep, duplicate, duplicate)).unwrap();
+        db.execute("INSERT INTO collection_contacts (customer_id, contact_at, method, outcome) VALUES (?, NOW(), 'call', 'promised')", one_param(duplicate))
+            .unwrap();
+    });
+
+    let result = merge_customers(t.db_state(), keep, vec![duplicate]).unwrap();
+    assert_eq!(result.merged_ids, vec![duplicate]);
+    assert_eq!(t.count(&format!("SELECT COUNT(*) FROM customers WHERE id = {}", duplicate)), 0);
+    assert_eq!(t.count(&format!("SELECT COUNT(*) FROM sales WHERE customer_id = {}", keep)), 1);
+    assert_eq!(t.count(&format!("SELECT COUNT(*) FROM collection_contacts WHERE customer_id = {}", keep)), 1);
+    // The shared tag is kept once
+    assert_eq!(t.count(&format!("SELECT COUNT(*) FROM customer_tags WHERE customer_id = {}", keep)), 2);
+}
+
+#[test]
+fn finalized_period_rejects_account_and_journal_changes() {
+    let t = TestDb::new();
+    let (account, currency) = t.with_db(|db| {
+        let currency = db.query("SELECT id FROM currencies WHERE base = 1 LIMIT 1", (), |row| Ok(row_get::<i64>(row, 0)?)).unwrap()[0];
+        let account = db.insert("INSERT INTO accounts (name, currency_id) VALUES ('Cash', ?)", one_param(currency)).unwrap();
+        db.insert("INSERT INTO journal_entries (entry_number, entry_date, description) VALUES ('JE-T1', '2024-02-01', 'Opening cash')", ()).unwrap();
+        (account, currency)
+    });
+    let entry = t.count("SELECT id FROM journal_entries WHERE entry_number = 'JE-T1'");
+    finalize_period(t.db_state(), t.session(), "2024-02".to_string(), None, None, None).unwrap();
+
+    let finalized = |error: String| error.contains("finalized");
+    let deposit = |date: &str| deposit_account(t.db_state(), account, 500.0, "افغانی".to_string(), 1.0, date.to_string(), false, None);
+    assert!(finalized(deposit("2024-02-10 09:30:00").unwrap_err()));
+    let withdraw = withdraw_account(t.db_state(), account, 100.0, "افغانی".to_string(), 1.0, "2024-02-10".to_string(), false, None);
+    assert!(finalized(withdraw.unwrap_err()));
+    let lines = vec![(account, currency, 100.0, 0.0, 1.0, None)];
+    let created = create_journal_entry(t.db_state(), "2024-02-10".to_string(), None, None, None, lines.clone());
+    assert!(finalized(created.unwrap_err()));
+    assert!(finalized(update_journal_entry(t.db_state(), entry, lines).unwrap_err()));
+    assert_eq!(t.count("SELECT COUNT(*) FROM account_transactions"), 0);
+
+    // Outside the period they go through
+    deposit("2024-03-01").unwrap();
+}
+
+#[test]
+fn restricted_roles_only_read_their_own_sales_and_customers() {
+    let t = TestDb::new();
+    let product = t.product("Tea").price(50.0).batch(10.0, 30.0).create();
+    let batch = t.batches(product)[0];
+    let customer = t.customer("Nadia");
+    let sale = t.sale(customer).item(product, batch, 1.0, 50.0).create().unwrap();
+    let clerk = t.with_db(|db| {
+        db.insert("INSERT INTO users (username, email, password_hash, role) VALUES ('clerk', 'clerk@example.com', '', 'salesperson')", ())
+            .unwrap()
+    });
+    let admin = t.session().lock().unwrap().replace(SessionUser {
+        id: clerk,
+        username: "clerk".to_string(),
+        role: "salesperson".to_string(),
+        token: String::new(),
+        permissions: Vec::new(),
+        locked: false,
+        auto_lock_minutes: 0,
+        last_active: None,
+    });
+
+    // Someone else's sale and customer read as missing, and the clerk cannot assign the customer to themselves
+    assert!(get_sale(t.db_state(), t.session(), sale.id).is_err());
+    assert!(get_sale_payments(t.db_state(), t.session(), sale.id).is_err());
+    assert!(get_customer_ledger(t.db_state(), t.session(), customer, None, None).is_err());
+    assert!(assign_customer_to_user(t.db_state(), t.session(), customer, Some(clerk)).is_err());
+
+    // Once an administrator assigns the customer, the clerk sees it and its sales
+    let clerk_session = t.session().lock().unwrap().replace(admin.unwrap());
+    assign_customer_to_user(t.db_state(), t.session(), customer, Some(clerk)).unwrap();
+    *t.session().lock().unwrap() = clerk_session;
+    assert_eq!(get_sale(t.db_state(), t.session(), sale.id).unwrap().0.id, sale.id);
+    assert_eq!(get_customer_tags(t.db_state(), t.session(), customer).unwrap(), Vec::<String>::new());
+}
+
+#[test]
+fn salary_totals_stay_queryable_with_field_encryption_on() {
+    let t = TestDb::new();
+    let employee = t.with_db(|db| {
+        db.execute("INSERT INTO app_settings (setting_key, value) VALUES ('field_encryption_enabled', '1')", ()).unwrap();
+        db.insert("INSERT INTO employees (full_name, phone, address, position, hire_date, base_salary) VALUES ('Wahid', '0790000001', 'Kabul', 'Clerk', '2024-01-01', 12000)", ())
+            .unwrap()
+    });
+    let january = create_salary(t.db_state(), employee, 2024, "January".to_string(), 12000.0, 500.0, None).unwrap();
+    create_salary(t.db_state(), employee, 2024, "February".to_string(), 12500.0, 0.0, None).unwrap();
+    update_salary(t.db_state(), january.id, employee, 2024, "January".to_string(), 13000.0, 500.0, None).unwrap();
+
+    // Reports and the web API sum the column in SQL
+    assert_eq!(t.scalar("SELECT SUM(amount) FROM salaries WHERE employee_id = ?", one_param(employee)), 25500.0);
+    assert_eq!(t.count("SELECT COUNT(*) FROM salaries WHERE amount_enc IS NOT NULL"), 0);
+    let listed: f64 = get_salaries_by_employee(t.db_state(), employee).unwrap().iter().map(|s| s.amount).sum();
+    assert_eq!(listed, 25500.0);
+}
+
+#[test]
+fn anonymizing_a_customer_scrubs_their_storefront_orders() {
+    let t = TestDb::new();
+    let product = t.product("Soap").price(20.0).batch(10.0, 12.0).create();
+    let batch = t.batches(product)[0];
+    let customer = t.customer("Laila");
+    let sale = t.sale(customer).item(product, batch, 1.0, 20.0).create().unwrap();
+    t.with_db(|db| {
+        db.execute("UPDATE sales SET notes = 'Online order #W-7 — Laila (0790000000)' WHERE id = ?", one_param(sale.id)).unwrap();
+        db.execute(
+            "INSERT INTO storefront_orders (remote_id, number, status, customer_name, phone, address, total, ordered_at, sale_id)
+            VALUES (7, 'W-7', 'accepted', 'Laila', '0790000000', 'Kabul', 20, '2024-02-01', ?), (8, 'W-8', 'draft', 'Laila', '+93790000000', 'Kabul', 20, '2024-02-02', NULL)",
+            one_param(sale.id),
+        )
+        .unwrap();
+    });
+
+    anonymize_customer(t.db_state(), customer).unwrap();
+    assert_eq!(t.count("SELECT COUNT(*) FROM storefront_orders WHERE customer_name <> '' OR phone IS NOT NULL OR address IS NOT NULL"), 0);
+    assert_eq!(t.count(&format!("SELECT COUNT(*) FROM sales WHERE id = {} AND notes = 'Online order #W-7'", sale.id)), 1);
+    assert_eq!(t.count(&format!("SELECT COUNT(*) FROM customers WHERE id = {} AND phone = ''", customer)), 1);
+}
+
+#[test]
+fn wrong_pins_lock_the_user_out_after_the_limit() {
+    let t = TestDb::new();
+    let user_id = t.with_db(|db| {
+        let pin_hash = bcrypt::hash("4321", 4).unwrap();
+        db.execute("UPDATE users SET pin_hash = ? WHERE username = 'testuser'", one_param(pin_hash.as_str())).unwrap();
+        db.query("SELECT id FROM users WHERE username = 'testuser'", (), |row| Ok(row_get::<i64>(row, 0)?)).unwrap()[0]
+    });
+    t.session().lock().unwrap().as_mut().unwrap().locked = true;
+
+    for _ in 0..PIN_MAX_ATTEMPTS {
+        assert!(!unlock_with_pin(t.db_state(), t.session(), None, "0000".to_string()).unwrap().success);
+    }
+    // The right PIN no longer unlocks, and refused tries do not push the count past the limit
+    let refused = unlock_with_pin(t.db_state(), t.session(), None, "4321".to_string()).unwrap();
+    assert!(!refused.success);
+    assert_eq!(refused.message, "Too many wrong PINs; sign in with the password");
+    assert_eq!(t.count(&format!("SELECT pin_failed_attempts FROM users WHERE id = {}", user_id)), PIN_MAX_ATTEMPTS);
+
+    // Below the limit the right PIN unlocks and resets the count
+    t.with_db(|db| db.execute("UPDATE users SET pin_failed_attempts = 1 WHERE id = ?", one_param(user_id)).unwrap());
+    assert!(unlock_with_pin(t.db_state(), t.session(), None, "4321".to_string()).unwrap().success);
+    assert_eq!(t.count(&format!("SELECT pin_failed_attempts FROM users WHERE id = {}", user_id)), 0);
+}
@@ -0,0 +1,81 @@
+//! Terminal settings kept in the .env file next to the MySQL connection, because they belong to this computer rather
+//! than to the shared database: its display name, its auto-lock override, its backup folder, its receipt printer and
+//! the warehouse (branch) it sells from.
+
+pub const TERMINAL_NAME: &str = "TERMINAL_NAME";
+pub const AUTO_LOCK_MINUTES: &str = "AUTO_LOCK_MINUTES";
+pub const BACKUP_DIR: &str = "BACKUP_DIR";
+pub const RECEIPT_PRINTER: &str = "RECEIPT_PRINTER";
+pub const WAREHOUSE_ID: &str = "WAREHOUSE_ID";
+
+pub const MAX_TERMINAL_NAME_LEN: usize = 64;
+pub const MAX_AUTO_LOCK_MINUTES: i64 = 240;
+
+/// `content` with each KEY=value line set: lines already there are replaced in place, the others appended.
+pub fn set_values(content: &str, values: &[(&str, String)]) -> String {
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    for (key, value) in values {
+        let prefix = format!("{}=", key);
+        match lines.iter_mut().find(|line| line.starts_with(&prefix)) {
+            Some(line) => *line = format!("{}{}", prefix, value),
+            None => lines.push(format!("{}{}", prefix, value)),
+        }
+    }
+    lines.join("\n")
+}
+
+/// A terminal name as stored: trimmed, at most 64 characters and a single line.
+pub fn terminal_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.chars().count() > MAX_TERMINAL_NAME_LEN || name.chars().any(char::is_control) {
+        return Err(format!("Terminal name must be a single line of at most {} characters", MAX_TERMINAL_NAME_LEN));
+    }
+    Ok(name.to_string())
+}
+
+pub fn auto_lock_minutes(minutes: i64) -> Result<i64, String> {
+    if !(0..=MAX_AUTO_LOCK_MINUTES).contains(&minutes) {
+        return Err(format!("Auto-lock must be between 0 and {} minutes", MAX_AUTO_LOCK_MINUTES));
+    }
+    Ok(minutes)
+}
+
+/// A receipt printer written as "host", "host:port" or "usb:<device>": (address, port when given).
+pub fn parse_printer(printer: &str) -> Result<(String, Option<u16>), String> {
+    let printer = printer.trim();
+    if printer.is_empty() || printer.chars().any(char::is_whitespace) {
+        return Err(format!("Invalid printer address: {}", printer));
+    }
+    if printer.starts_with("usb:") {
+        return Ok((printer.to_string(), None));
+    }
+    match printer.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() => {
+            let port = port.parse::<u16>().ok().filter(|p| *p > 0).ok_or_else(|| format!("Invalid printer port: {}", port))?;
+            Ok((host.to_string(), Some(port)))
+        }
+        Some(_) => Err(format!("Invalid printer address: {}", printer)),
+        None => Ok((printer.to_string(), None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminal_settings() {
+        let content = "MYSQL_HOST=127.0.0.1\n# comment\nTERMINAL_NAME=Old";
+        let updated = set_values(content, &[(TERMINAL_NAME, "Counter 1".to_string()), (RECEIPT_PRINTER, "10.0.0.9:9100".to_string())]);
+        assert_eq!(updated, "MYSQL_HOST=127.0.0.1\n# comment\nTERMINAL_NAME=Counter 1\nRECEIPT_PRINTER=10.0.0.9:9100");
+        assert_eq!(parse_printer("10.0.0.9:9100"), Ok(("10.0.0.9".to_string(), Some(9100))));
+        assert_eq!(parse_printer("printer.local"), Ok(("printer.local".to_string(), None)));
+        assert_eq!(parse_printer("usb:/dev/usb/lp0"), Ok(("usb:/dev/usb/lp0".to_string(), None)));
+        assert!(parse_printer("10.0.0.9:port").is_err());
+        assert!(parse_printer(":9100").is_err());
+        assert_eq!(terminal_name("  Counter 1 "), Ok("Counter 1".to_string()));
+        assert!(terminal_name("a\nb").is_err());
+        assert!(auto_lock_minutes(241).is_err());
+        assert_eq!(auto_lock_minutes(15), Ok(15));
+    }
+}
@@ -0,0 +1,152 @@
+//! Optional field-level encryption for sensitive columns (customer phones, profile pictures).
+//! The key is derived from the machine ID + license key, so data is only readable on the licensed install.
+//! Encryption is deterministic (nonce derived from key + plaintext) so equality lookups on encrypted columns still work;
+//! searches go through a keyed blind index of the normalized value instead.
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use sha2::{Digest, Sha256};
+
+/// Marks an encrypted value: "enc1:" + hex(nonce || ciphertext).
+pub const ENC_PREFIX: &str = "enc1:";
+const FIELD_KEY_SALT: &str = "finance-app-field-key-2024";
+const BLIND_INDEX_SALT: &str = "finance-app-blind-index-2024";
+
+pub struct FieldCipher {
+    key: Option<[u8; 32]>,
+    /// When false, encrypt() passes values through; decrypt() still works for rows encrypted earlier.
+    enabled: bool,
+}
+
+impl FieldCipher {
+    pub fn new(key: Option<[u8; 32]>, enabled: bool) -> Self {
+        FieldCipher { key, enabled: enabled && key.is_some() }
+    }
+
+    pub fn derive_key(machine_id: &str, license_key: &str) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(FIELD_KEY_SALT.as_bytes());
+        hasher.update(machine_id.as_bytes());
+        hasher.update(license_key.trim().as_bytes());
+        hasher.finalize().into()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn is_encrypted(value: &str) -> bool {
+        value.starts_with(ENC_PREFIX)
+    }
+
+    /// Encrypt when enabled; empty and already-encrypted values are returned unchanged.
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, String> {
+        match self.key {
+            Some(key) if self.enabled && !plaintext.is_empty() && !Self::is_encrypted(plaintext) => encrypt_with(&key, plaintext),
+            _ => Ok(plaintext.to_string()),
+        }
+    }
+
+    pub fn encrypt_opt(&self, plaintext: Option<&str>) -> Result<Option<String>, String> {
+        plaintext.map(|p| self.encrypt(p)).transpose()
+    }
+
+    /// Decrypt an encrypted value; plain values (and values we have no key for) are returned unchanged.
+    pub fn decrypt(&self, value: &str) -> String {
+        match (self.key, value.strip_prefix(ENC_PREFIX)) {
+            (Some(key), Some(hex_data)) => decrypt_with(&key, hex_data).unwrap_or_else(|_| value.to_string()),
+            _ => value.to_string(),
+        }
+    }
+
+    /// Keyed hash of a normalized value (32 hex chars) for exact-match search on an encrypted column; None when
+    /// encryption is off or the value is empty. Different keys give unrelated indexes.
+    pub fn blind_index(&self, normalized: &str) -> Option<String> {
+        let key = self.key.filter(|_| self.enabled && !normalized.is_empty())?;
+        let mut hasher = Sha256::new();
+        hasher.update(BLIND_INDEX_SALT.as_bytes());
+        hasher.update(key);
+        hasher.update(normalized.as_bytes());
+        Some(hex::encode(&hasher.finalize()[..16]))
+    }
+
+    pub fn decrypt_opt(&self, value: Option<String>) -> Option<String> {
+        value.map(|v| self.decrypt(&v))
+    }
+
+    /// Decrypt with the key regardless of the enabled flag, failing loudly (used by migrations).
+    pub fn decrypt_strict(&self, value: &str) -> Result<String, String> {
+        match (self.key, value.strip_prefix(ENC_PREFIX)) {
+            (_, None) => Ok(value.to_string()),
+            (Some(key), Some(hex_data)) => decrypt_with(&key, hex_data),
+            (None, Some(_)) => Err("No license key available to decrypt data".to_string()),
+        }
+    }
+}
+
+fn encrypt_with(key: &[u8; 32], plaintext: &str) -> Result<String, String> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| format!("Failed to create cipher: {}", e))?;
+    let mut hasher = Sha256::new();
+    hasher.update(key);
+    hasher.update(plaintext.as_bytes());
+    let nonce_hash = hasher.finalize();
+    let nonce = Nonce::from_slice(&nonce_hash[..12]);
+    let ciphertext = cipher
+        .encrypt(nonce, plaintext.as_bytes())
+        .map_err(|e| format!("Encryption error: {}", e))?;
+    let mut combined = nonce.to_vec();
+    combined.extend_from_slice(&ciphertext);
+    Ok(format!("{}{}", ENC_PREFIX, hex::encode(combined)))
+}
+
+fn decrypt_with(key: &[u8; 32], hex_data: &str) -> Result<String, String> {
+    let bytes = hex::decode(hex_data).map_err(|e| format!("Invalid hex: {}", e))?;
+    if bytes.len() < 12 {
+        return Err("Ciphertext too short".to_string());
+    }
+    let (nonce_bytes, ciphertext) = bytes.split_at(12);
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| format!("Failed to create cipher: {}", e))?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|e| format!("Decryption error: {}", e))?;
+    String::from_utf8(plaintext).map_err(|e| format!("Invalid UTF-8: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_roundtrip_and_passthrough() {
+        let key = FieldCipher::derive_key("machine", "LICENSE-1");
+        let cipher = FieldCipher::new(Some(key), true);
+        let enc = cipher.encrypt("+93799123456").unwrap();
+        assert!(FieldCipher::is_encrypted(&enc));
+        assert_eq!(enc, cipher.encrypt("+93799123456").unwrap());
+        assert_eq!(cipher.decrypt(&enc), "+93799123456");
+
+        let disabled = FieldCipher::new(Some(key), false);
+        assert_eq!(disabled.encrypt("plain").unwrap(), "plain");
+        assert_eq!(disabled.decrypt(&enc), "+93799123456");
+
+        let wrong = FieldCipher::new(Some(FieldCipher::derive_key("other", "LICENSE-1")), true);
+        assert!(wrong.decrypt_strict(&enc).is_err());
+    }
+
+    #[test]
+    fn test_blind_index() {
+        let cipher = FieldCipher::new(Some(FieldCipher::derive_key("machine", "LICENSE-1")), true);
+        let index = cipher.blind_index("799123456").unwrap();
+        assert_eq!(index.len(), 32);
+        assert_eq!(cipher.blind_index("799123456"), Some(index.clone()));
+        assert_ne!(cipher.blind_index("799123457"), Some(index.clone()));
+        assert!(!index.contains("799123456"));
+        assert_eq!(cipher.blind_index(""), None);
+
+        let other = FieldCipher::new(Some(FieldCipher::derive_key("machine", "LICENSE-2")), true);
+        assert_ne!(other.blind_index("799123456"), Some(index));
+        assert_eq!(FieldCipher::new(Some(FieldCipher::derive_key("machine", "LICENSE-1")), false).blind_index("799123456"), None);
+    }
+}
@@ -0,0 +1,49 @@
+//! Currency exchange between two balances of one account, valued at the official rates to the base currency.
+
+/// Both legs of an exchange and the realized gain (negative: loss), all in base currency except `amount_to`.
+#[derive(Debug, PartialEq)]
+pub struct Exchange {
+    /// Received, in the target currency.
+    pub amount_to: f64,
+    pub out_base: f64,
+    pub in_base: f64,
+    pub gain_loss: f64,
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+/// Exchange `amount` of one currency at `deal_rate` (target units per source unit), where `official_from` and
+/// `official_to` are the official rates of the two currencies to the base currency. The gain is what the received
+/// amount is worth beyond what was given up, both at official rates.
+pub fn exchange(amount: f64, deal_rate: f64, official_from: f64, official_to: f64) -> Exchange {
+    let amount_to = round2(amount * deal_rate);
+    let out_base = round2(amount * official_from);
+    let in_base = round2(amount_to * official_to);
+    Exchange { amount_to, out_base, in_base, gain_loss: round2(in_base - out_base) }
+}
+
+/// Official cross rate: target units per source unit.
+pub fn official_cross_rate(official_from: f64, official_to: f64) -> f64 {
+    if official_to <= 0.0 {
+        return 0.0;
+    }
+    (official_from / official_to * 1_000_000.0).round() / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exchange() {
+        // 100 USD sold at 71 AFN while the official rate is 70: a gain of 100 AFN
+        assert_eq!(exchange(100.0, 71.0, 70.0, 1.0), Exchange { amount_to: 7100.0, out_base: 7000.0, in_base: 7100.0, gain_loss: 100.0 });
+        // 7000 AFN bought back at 0.0140 USD per afghani: 98 USD, worth 6860 at the official rate
+        assert_eq!(exchange(7000.0, 0.014, 1.0, 70.0).gain_loss, -140.0);
+        assert_eq!(official_cross_rate(70.0, 1.0), 70.0);
+        assert_eq!(official_cross_rate(1.0, 70.0), 0.014286);
+        assert_eq!(official_cross_rate(1.0, 0.0), 0.0);
+    }
+}
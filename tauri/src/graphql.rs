@@ -0,0 +1,139 @@
+//! Optional read-only GraphQL endpoint (POST /graphql), built with the `graphql` feature.
+//! Uses the same Bearer API tokens, scopes and rate limits as the REST API; each top-level field counts as one request.
+
+use crate::server::{
+    bearer_token, json_response, run_authorized_query, stock_sql, ApiState, CUSTOMERS_SQL, PRODUCTS_SQL, SALES_SQL,
+};
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema, SimpleObject};
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderMap, Response, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+type ReportSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Per-request data handed to resolvers.
+struct GraphqlAuth {
+    state: ApiState,
+    token: Option<String>,
+}
+
+#[derive(Debug, Deserialize, SimpleObject)]
+struct Product {
+    id: i64,
+    name: String,
+    description: Option<String>,
+    price: Option<f64>,
+    currency_id: Option<i64>,
+    supplier_id: Option<i64>,
+    stock_quantity: Option<f64>,
+    unit: Option<String>,
+    bar_code: Option<String>,
+    created_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, SimpleObject)]
+struct Customer {
+    id: i64,
+    full_name: String,
+    phone: Option<String>,
+    address: Option<String>,
+    email: Option<String>,
+    created_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, SimpleObject)]
+struct Sale {
+    id: i64,
+    customer_id: i64,
+    date: String,
+    currency_id: Option<i64>,
+    exchange_rate: f64,
+    total_amount: f64,
+    base_amount: f64,
+    paid_amount: f64,
+    created_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, SimpleObject)]
+struct StockLevel {
+    product_id: i64,
+    name: String,
+    /// Remaining quantity across all batches, in base units.
+    stock_base: f64,
+}
+
+/// Run a shared list query for a resolver, off the async runtime.
+async fn fetch<T: DeserializeOwned>(
+    ctx: &Context<'_>,
+    scope: &'static str,
+    sql: &'static str,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> async_graphql::Result<Vec<T>> {
+    let auth = ctx.data::<GraphqlAuth>()?;
+    let token = auth.token.clone().ok_or("Missing Bearer token")?;
+    let state = auth.state.clone();
+    let limit = limit.unwrap_or(100).clamp(1, 1000);
+    let offset = offset.unwrap_or(0).max(0);
+
+    let rows = tokio::task::spawn_blocking(move || {
+        run_authorized_query(&state, &token, scope, sql, vec![limit.into(), offset.into()])
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|(_, message)| message)?;
+
+    rows.into_iter()
+        .map(|row| serde_json::from_value(row).map_err(|e| async_graphql::Error::new(e.to_string())))
+        .collect()
+}
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn products(&self, ctx: &Context<'_>, limit: Option<i64>, offset: Option<i64>) -> async_graphql::Result<Vec<Product>> {
+        fetch(ctx, "products:read", PRODUCTS_SQL, limit, offset).await
+    }
+
+    async fn customers(&self, ctx: &Context<'_>, limit: Option<i64>, offset: Option<i64>) -> async_graphql::Result<Vec<Customer>> {
+        fetch(ctx, "customers:read", CUSTOMERS_SQL, limit, offset).await
+    }
+
+    async fn sales(&self, ctx: &Context<'_>, limit: Option<i64>, offset: Option<i64>) -> async_graphql::Result<Vec<Sale>> {
+        fetch(ctx, "sales:read", SALES_SQL, limit, offset).await
+    }
+
+    async fn stock(&self, ctx: &Context<'_>, limit: Option<i64>, offset: Option<i64>) -> async_graphql::Result<Vec<StockLevel>> {
+        fetch(ctx, "stock:read", stock_sql(), limit, offset).await
+    }
+}
+
+/// Router exposing POST /graphql; merged into the embedded server when the feature is enabled.
+pub(crate) fn router(state: ApiState) -> Router {
+    let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(5)
+        .finish();
+    Router::new()
+        .route("/graphql", post(graphql_handler))
+        .with_state((schema, state))
+}
+
+async fn graphql_handler(
+    State((schema, state)): State<(ReportSchema, ApiState)>,
+    headers: HeaderMap,
+    Json(request): Json<async_graphql::Request>,
+) -> Response<Body> {
+    let request = request.data(GraphqlAuth {
+        state,
+        token: bearer_token(&headers),
+    });
+    let response = schema.execute(request).await;
+    json_response(StatusCode::OK, serde_json::to_value(&response).unwrap_or_default())
+}
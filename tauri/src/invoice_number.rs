@@ -0,0 +1,76 @@
+//! Invoice numbers of sales: a prefix, the sale's year when numbering restarts every year, and a zero-padded
+//! sequence, e.g. "INV-2024-000042". The sequence itself is kept per year in the database.
+
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PREFIX: &str = "INV-";
+pub const DEFAULT_PADDING: u32 = 6;
+pub const MAX_PREFIX_LEN: usize = 16;
+pub const MAX_PADDING: u32 = 12;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NumberScheme {
+    pub prefix: String,
+    /// Minimum digits of the sequence; longer sequences are not cut.
+    pub padding: u32,
+    pub yearly_reset: bool,
+}
+
+impl Default for NumberScheme {
+    fn default() -> Self {
+        NumberScheme { prefix: DEFAULT_PREFIX.to_string(), padding: DEFAULT_PADDING, yearly_reset: true }
+    }
+}
+
+/// A scheme as stored: the prefix trimmed, at most 16 characters without spaces or control characters, and a
+/// padding of 1 to 12 digits.
+pub fn validate(prefix: &str, padding: u32, yearly_reset: bool) -> Result<NumberScheme, String> {
+    let prefix = prefix.trim();
+    if prefix.chars().count() > MAX_PREFIX_LEN || prefix.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!("Invoice prefix must be at most {} characters without spaces", MAX_PREFIX_LEN));
+    }
+    if !(1..=MAX_PADDING).contains(&padding) {
+        return Err(format!("Invoice number padding must be between 1 and {} digits", MAX_PADDING));
+    }
+    Ok(NumberScheme { prefix: prefix.to_string(), padding, yearly_reset })
+}
+
+/// The sequence a sale dated `date` ("YYYY-MM-DD...") counts in: its year with a yearly reset, else 0 for one
+/// sequence that never restarts.
+pub fn sequence_year(scheme: &NumberScheme, date: &str) -> Result<i32, String> {
+    if !scheme.yearly_reset {
+        return Ok(0);
+    }
+    date.trim().get(0..4).and_then(|y| y.parse::<i32>().ok()).filter(|y| *y > 0).ok_or_else(|| format!("Invalid date: {}", date))
+}
+
+pub fn format(scheme: &NumberScheme, year: i32, sequence: i64) -> String {
+    let width = scheme.padding as usize;
+    if scheme.yearly_reset {
+        format!("{}{}-{:0width$}", scheme.prefix, year, sequence)
+    } else {
+        format!("{}{:0width$}", scheme.prefix, sequence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invoice_numbers() {
+        let yearly = NumberScheme::default();
+        let year = sequence_year(&yearly, "2024-02-01 10:30:00").unwrap();
+        assert_eq!(year, 2024);
+        assert_eq!(format(&yearly, year, 42), "INV-2024-000042");
+        assert!(sequence_year(&yearly, "pending").is_err());
+
+        let running = validate(" S/ ", 4, false).unwrap();
+        assert_eq!(sequence_year(&running, "2024-02-01").unwrap(), 0);
+        assert_eq!(format(&running, 0, 7), "S/0007");
+        assert_eq!(format(&running, 0, 123456), "S/123456");
+        assert!(validate("INV 1", 6, true).is_err());
+        assert!(validate("INV-", 0, true).is_err());
+        assert!(validate("ABCDEFGHIJKLMNOPQ", 6, true).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+//! Splitting long invoices into printed pages with brought-forward / carried-forward subtotals.
+
+/// One printed page: lines `start..end` of the invoice plus the running totals around them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    /// 1-based page number.
+    pub number: usize,
+    pub start: usize,
+    pub end: usize,
+    /// Sum of the lines on earlier pages.
+    pub brought_forward: f64,
+    pub page_total: f64,
+    /// brought_forward + page_total.
+    pub carried_forward: f64,
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+/// Paginate lines with `first_page` rows on page 1 (which also holds the document header) and `per_page` rows on
+/// the following pages. The last page must keep `summary_rows` free for the totals block; if it cannot, the totals
+/// move to an extra page without lines. Always returns at least one page.
+pub fn paginate(line_totals: &[f64], first_page: usize, per_page: usize, summary_rows: usize) -> Vec<Page> {
+    let first_page = first_page.max(1);
+    let per_page = per_page.max(1);
+    let mut bounds = Vec::new();
+    let mut start = 0;
+    let mut capacity = first_page;
+    while start < line_totals.len() {
+        let end = (start + capacity).min(line_totals.len());
+        bounds.push((start, end, capacity));
+        start = end;
+        capacity = per_page;
+    }
+    match bounds.last() {
+        None => bounds.push((0, 0, first_page)),
+        Some(&(s, e, cap)) if cap.saturating_sub(e - s) < summary_rows => bounds.push((e, e, per_page)),
+        Some(_) => {}
+    }
+
+    let mut running = 0.0;
+    bounds
+        .into_iter()
+        .enumerate()
+        .map(|(i, (start, end, _))| {
+            let page_total = round2(line_totals[start..end].iter().sum());
+            let brought_forward = round2(running);
+            running += page_total;
+            Page { number: i + 1, start, end, brought_forward, page_total, carried_forward: round2(running) }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate() {
+        let lines = vec![1.0; 105];
+        let pages = paginate(&lines, 20, 30, 5);
+        assert_eq!(pages.len(), 4);
+        assert_eq!((pages[0].start, pages[0].end), (0, 20));
+        assert_eq!((pages[3].start, pages[3].end), (80, 105));
+        assert_eq!(pages[1].brought_forward, 20.0);
+        assert_eq!(pages[1].carried_forward, 50.0);
+        assert_eq!(pages[3].carried_forward, 105.0);
+
+        // A full last page pushes the totals block onto an extra empty page
+        let pages = paginate(&[2.5; 20], 20, 30, 5);
+        assert_eq!(pages.len(), 2);
+        assert_eq!((pages[1].start, pages[1].end, pages[1].brought_forward), (20, 20, 50.0));
+
+        let pages = paginate(&[], 20, 30, 5);
+        assert_eq!(pages, vec![Page { number: 1, start: 0, end: 0, brought_forward: 0.0, page_total: 0.0, carried_forward: 0.0 }]);
+    }
+}
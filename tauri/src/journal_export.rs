@@ -0,0 +1,177 @@
+//! Journal export layouts for external accounting systems (QuickBooks, Xero, generic debit/credit CSV).
+
+use serde_json::{json, Value};
+
+/// Supported export formats.
+pub const FORMATS: [&str; 3] = ["quickbooks", "xero", "generic"];
+
+/// One journal line ready for export; amounts are in base currency except `debit`/`credit` (line currency).
+#[derive(Debug, Clone)]
+pub struct ExportLine {
+    pub entry_number: String,
+    /// "YYYY-MM-DD"
+    pub date: String,
+    pub narration: String,
+    pub reference: String,
+    pub account_code: String,
+    pub account_name: String,
+    pub description: String,
+    pub debit: f64,
+    pub credit: f64,
+    pub currency: String,
+    pub exchange_rate: f64,
+    pub base_debit: f64,
+    pub base_credit: f64,
+}
+
+/// Date range for a period: "YYYY", "YYYY-MM" or "YYYY-MM-DD:YYYY-MM-DD" (inclusive, compared as text).
+pub fn period_range(period: &str) -> Option<(String, String)> {
+    let p = period.trim();
+    if let Some((from, to)) = p.split_once(':') {
+        let (from, to) = (from.trim(), to.trim());
+        return (is_date(from) && is_date(to) && from <= to).then(|| (from.to_string(), to.to_string()));
+    }
+    match p.len() {
+        4 if p.chars().all(|c| c.is_ascii_digit()) => Some((format!("{}-01-01", p), format!("{}-12-31", p))),
+        7 if is_date(&format!("{}-01", p)) => Some((format!("{}-01", p), format!("{}-31", p))),
+        _ => None,
+    }
+}
+
+fn is_date(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() == 10
+        && b[4] == b'-'
+        && b[7] == b'-'
+        && b.iter().enumerate().all(|(i, c)| i == 4 || i == 7 || c.is_ascii_digit())
+        && matches!(s[5..7].parse::<u32>(), Ok(1..=12))
+        && matches!(s[8..10].parse::<u32>(), Ok(1..=31))
+}
+
+/// "YYYY-MM-DD" → "MM/DD/YYYY" (QuickBooks) or "DD/MM/YYYY" (Xero); other input is returned unchanged.
+fn slash_date(date: &str, month_first: bool) -> String {
+    let d = date.get(0..10).unwrap_or(date);
+    if !is_date(d) {
+        return date.to_string();
+    }
+    if month_first {
+        format!("{}/{}/{}", &d[5..7], &d[8..10], &d[0..4])
+    } else {
+        format!("{}/{}/{}", &d[8..10], &d[5..7], &d[0..4])
+    }
+}
+
+fn amount(v: f64) -> Value {
+    if v.abs() < 0.005 {
+        Value::String(String::new())
+    } else {
+        json!((v * 100.0).round() / 100.0)
+    }
+}
+
+/// Header and rows of the export in the given format (unknown formats fall back to generic).
+pub fn to_rows(format: &str, lines: &[ExportLine]) -> (Vec<String>, Vec<Vec<Value>>) {
+    let columns = |names: &[&str]| names.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+    match format {
+        "quickbooks" => (
+            columns(&["Journal No", "Journal Date", "Account", "Debits", "Credits", "Description", "Memo"]),
+            lines
+                .iter()
+                .map(|l| {
+                    vec![
+                        json!(l.entry_number),
+                        json!(slash_date(&l.date, true)),
+                        json!(if l.account_code.is_empty() { l.account_name.clone() } else { l.account_code.clone() }),
+                        amount(l.base_debit),
+                        amount(l.base_credit),
+                        json!(l.description),
+                        json!(l.narration),
+                    ]
+                })
+                .collect(),
+        ),
+        "xero" => (
+            columns(&["*Narration", "*Date", "Description", "*AccountCode", "*TaxRate", "*Amount", "Reference"]),
+            lines
+                .iter()
+                .map(|l| {
+                    vec![
+                        json!(if l.narration.is_empty() { l.entry_number.clone() } else { l.narration.clone() }),
+                        json!(slash_date(&l.date, false)),
+                        json!(l.description),
+                        json!(l.account_code),
+                        json!("Tax Exempt"),
+                        // Xero manual journals: debits positive, credits negative
+                        json!(((l.base_debit - l.base_credit) * 100.0).round() / 100.0),
+                        json!(l.entry_number),
+                    ]
+                })
+                .collect(),
+        ),
+        _ => (
+            columns(&[
+                "entry_number", "date", "reference", "account_code", "account_name", "description", "debit", "credit",
+                "currency", "exchange_rate", "base_debit", "base_credit",
+            ]),
+            lines
+                .iter()
+                .map(|l| {
+                    vec![
+                        json!(l.entry_number),
+                        json!(l.date),
+                        json!(l.reference),
+                        json!(l.account_code),
+                        json!(l.account_name),
+                        json!(if l.description.is_empty() { l.narration.clone() } else { l.description.clone() }),
+                        json!(l.debit),
+                        json!(l.credit),
+                        json!(l.currency),
+                        json!(l.exchange_rate),
+                        json!(l.base_debit),
+                        json!(l.base_credit),
+                    ]
+                })
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_periods_and_layouts() {
+        assert_eq!(period_range("2024"), Some(("2024-01-01".into(), "2024-12-31".into())));
+        assert_eq!(period_range("2024-02"), Some(("2024-02-01".into(), "2024-02-31".into())));
+        assert_eq!(period_range("2024-01-05:2024-01-20"), Some(("2024-01-05".into(), "2024-01-20".into())));
+        assert_eq!(period_range("2024-13"), None);
+        assert_eq!(period_range("2024-02-01:2024-01-01"), None);
+
+        let line = ExportLine {
+            entry_number: "JE-000001".into(),
+            date: "2024-03-05".into(),
+            narration: "Sale #7".into(),
+            reference: "sale #7".into(),
+            account_code: "1200".into(),
+            account_name: "Receivable".into(),
+            description: String::new(),
+            debit: 10.0,
+            credit: 0.0,
+            currency: "USD".into(),
+            exchange_rate: 70.0,
+            base_debit: 700.0,
+            base_credit: 0.0,
+        };
+        let (cols, rows) = to_rows("quickbooks", std::slice::from_ref(&line));
+        assert_eq!(cols[0], "Journal No");
+        assert_eq!(rows[0][1], json!("03/05/2024"));
+        assert_eq!(rows[0][4], json!(""));
+        let (_, rows) = to_rows("xero", &[ExportLine { base_debit: 0.0, base_credit: 700.0, ..line.clone() }]);
+        assert_eq!(rows[0][1], json!("05/03/2024"));
+        assert_eq!(rows[0][5], json!(-700.0));
+        let (cols, rows) = to_rows("generic", &[line]);
+        assert_eq!(cols.len(), rows[0].len());
+        assert_eq!(rows[0][5], json!("Sale #7"));
+    }
+}
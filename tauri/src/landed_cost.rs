@@ -0,0 +1,42 @@
+//! Allocation of shipment costs (customs, freight, clearance) across purchase lines.
+
+/// Allocation bases: line value (base currency), line weight or line quantity (base units).
+pub const METHODS: [&str; 3] = ["value", "weight", "quantity"];
+
+/// Split `total` proportionally to `bases`, rounded to 2 decimals with the last line absorbing rounding.
+/// Falls back to an equal split when every basis is zero.
+pub fn allocate(total: f64, bases: &[f64]) -> Vec<f64> {
+    if bases.is_empty() {
+        return Vec::new();
+    }
+    let sum: f64 = bases.iter().map(|b| b.max(0.0)).sum();
+    let n = bases.len();
+    let mut shares = Vec::with_capacity(n);
+    let mut allocated = 0.0;
+    for (i, basis) in bases.iter().enumerate() {
+        let share = if i == n - 1 {
+            total - allocated
+        } else if sum > 0.0 {
+            total * basis.max(0.0) / sum
+        } else {
+            total / n as f64
+        };
+        let share = (share * 100.0).round() / 100.0;
+        allocated += share;
+        shares.push(share);
+    }
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocate() {
+        assert_eq!(allocate(100.0, &[1.0, 1.0, 1.0]), vec![33.33, 33.33, 33.34]);
+        assert_eq!(allocate(90.0, &[200.0, 100.0]), vec![60.0, 30.0]);
+        assert_eq!(allocate(10.0, &[0.0, 0.0]), vec![5.0, 5.0]);
+        assert!(allocate(10.0, &[]).is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+//! Settings that can differ per location: a value may be set globally, for a warehouse (branch), for a terminal and
+//! for a user, and the most specific one set applies. Only the keys listed here are layered.
+
+use serde::{Deserialize, Serialize};
+
+/// Scopes from the least to the most specific.
+pub const SCOPES: [&str; 4] = ["global", "warehouse", "terminal", "user"];
+
+pub const PRICE_LEVEL: &str = "price_level";
+pub const RECEIPT_TEMPLATE_ID: &str = "receipt_template_id";
+pub const BACKUP_DIR: &str = "backup_dir";
+pub const RECEIPT_PRINTER: &str = "receipt_printer";
+pub const KEYS: [&str; 4] = [PRICE_LEVEL, RECEIPT_TEMPLATE_ID, BACKUP_DIR, RECEIPT_PRINTER];
+
+/// Batch prices a sale starts from.
+pub const PRICE_LEVELS: [&str; 2] = ["retail", "wholesale"];
+
+/// A value set at one scope; `scope_id` is the warehouse id, terminal name or user id ("" for global).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Layer {
+    pub scope: String,
+    pub scope_id: String,
+    pub value: String,
+}
+
+fn positive_id(value: &str) -> bool {
+    value.parse::<i64>().is_ok_and(|id| id > 0)
+}
+
+/// A value as stored for `key`: trimmed, single line, and for price_level and receipt_template_id one the app can use.
+pub fn validate(key: &str, value: &str) -> Result<String, String> {
+    let value = value.trim();
+    if !KEYS.contains(&key) {
+        return Err(format!("Unknown setting: {} (use {})", key, KEYS.join(", ")));
+    }
+    if value.is_empty() || value.chars().any(char::is_control) {
+        return Err(format!("Setting {} must be a single line", key));
+    }
+    match key {
+        PRICE_LEVEL if !PRICE_LEVELS.contains(&value) => Err(format!("Invalid price level: {} (use retail or wholesale)", value)),
+        RECEIPT_TEMPLATE_ID if !positive_id(value) => Err(format!("Invalid template id: {}", value)),
+        _ => Ok(value.to_string()),
+    }
+}
+
+/// A scope and its id as stored: global has no id, the others need one (a positive id for warehouses and users).
+pub fn scope_key(scope: &str, scope_id: Option<&str>) -> Result<(String, String), String> {
+    let id = scope_id.map(str::trim).unwrap_or("");
+    match scope.trim() {
+        "global" => Ok(("global".to_string(), String::new())),
+        "warehouse" | "user" if !positive_id(id) => Err(format!("A {} id is required", scope.trim())),
+        "terminal" if id.is_empty() || id.chars().any(char::is_control) => Err("A terminal name is required".to_string()),
+        s @ ("warehouse" | "user" | "terminal") => Ok((s.to_string(), id.to_string())),
+        other => Err(format!("Invalid scope: {} (use {})", other, SCOPES.join(", "))),
+    }
+}
+
+/// The value that applies among the layers of one key, and its layer: the most specific scope wins.
+pub fn resolve(layers: &[Layer]) -> Option<&Layer> {
+    layers.iter().max_by_key(|l| SCOPES.iter().position(|s| *s == l.scope))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layered_settings() {
+        let layer = |scope: &str, id: &str, value: &str| Layer { scope: scope.to_string(), scope_id: id.to_string(), value: value.to_string() };
+        let layers = vec![layer("terminal", "Counter 2", "wholesale"), layer("global", "", "retail"), layer("warehouse", "3", "retail")];
+        assert_eq!(resolve(&layers).map(|l| l.scope.as_str()), Some("terminal"));
+        assert_eq!(resolve(&layers[1..]).map(|l| l.value.as_str()), Some("retail"));
+        assert_eq!(resolve(&[]), None);
+
+        assert_eq!(validate(PRICE_LEVEL, " wholesale "), Ok("wholesale".to_string()));
+        assert!(validate(PRICE_LEVEL, "vip").is_err());
+        assert!(validate(RECEIPT_TEMPLATE_ID, "0").is_err());
+        assert!(validate(BACKUP_DIR, "D:\\Backups\nC:\\").is_err());
+        assert!(validate("theme", "dark").is_err());
+
+        assert_eq!(scope_key("global", Some("5")), Ok(("global".to_string(), String::new())));
+        assert_eq!(scope_key("warehouse", Some(" 3 ")), Ok(("warehouse".to_string(), "3".to_string())));
+        assert!(scope_key("user", None).is_err());
+        assert!(scope_key("terminal", Some("")).is_err());
+        assert!(scope_key("branch", Some("1")).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! HTTPS license API client with Ed25519-signed responses.
+//! REST mode is used when LICENSE_API_URL and LICENSE_API_PUBLIC_KEY were set at build time (URL may be overridden in .env);
+//! otherwise, or when the API is unreachable, the legacy MySQL license server is used.
+
+use crate::license_server::{self, LicenseCheckResult};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Server's Ed25519 public key (64 hex chars), embedded at build time.
+const LICENSE_API_PUBLIC_KEY: Option<&str> = option_env!("LICENSE_API_PUBLIC_KEY");
+/// Base URL of the license API, embedded at build time; LICENSE_API_URL in .env overrides it.
+const LICENSE_API_URL: Option<&str> = option_env!("LICENSE_API_URL");
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, Serialize)]
+struct LicenseRequest<'a> {
+    license_key: &'a str,
+    machine_id: String,
+    nonce: String,
+}
+
+/// Envelope returned by the API: payload is a JSON document (as a string) signed as-is.
+#[derive(Debug, Deserialize)]
+struct SignedResponse {
+    payload: String,
+    signature: String,
+}
+
+/// Signed license statement. nonce and license_key echo the request so a captured response can't be replayed.
+#[derive(Debug, Deserialize)]
+struct LicensePayload {
+    license_key: String,
+    nonce: String,
+    valid: bool,
+    reason: Option<String>,
+    expires_at: Option<String>,
+}
+
+/// Transport failures allow the legacy fallback; anything about the response itself (bad signature, mismatched nonce) does not.
+enum ApiError {
+    Unavailable(String),
+    Rejected(String),
+}
+
+struct ApiConfig {
+    base_url: String,
+    public_key: VerifyingKey,
+}
+
+fn api_config() -> Result<Option<ApiConfig>, String> {
+    let base_url = std::env::var("LICENSE_API_URL")
+        .ok()
+        .filter(|u| !u.trim().is_empty())
+        .or_else(|| LICENSE_API_URL.map(str::to_string));
+    let (base_url, key_hex) = match (base_url, LICENSE_API_PUBLIC_KEY) {
+        (Some(url), Some(key)) if !key.trim().is_empty() => (url, key),
+        _ => return Ok(None),
+    };
+    if !base_url.starts_with("https://") {
+        return Err("LICENSE_API_URL must use https".to_string());
+    }
+    Ok(Some(ApiConfig {
+        base_url: base_url.trim_end_matches('/').to_string(),
+        public_key: parse_public_key(key_hex)?,
+    }))
+}
+
+fn parse_public_key(key_hex: &str) -> Result<VerifyingKey, String> {
+    let bytes = hex::decode(key_hex.trim()).map_err(|e| format!("Invalid license public key: {}", e))?;
+    let bytes: [u8; 32] = bytes.try_into().map_err(|_| "License public key must be 32 bytes".to_string())?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| format!("Invalid license public key: {}", e))
+}
+
+/// Verify the Ed25519 signature over the raw payload bytes and decode it.
+fn verify_signed_payload(public_key: &VerifyingKey, response: &SignedResponse) -> Result<LicensePayload, String> {
+    let sig_bytes = hex::decode(response.signature.trim()).map_err(|e| format!("Invalid signature encoding: {}", e))?;
+    let signature = Signature::from_slice(&sig_bytes).map_err(|e| format!("Invalid signature: {}", e))?;
+    public_key
+        .verify(response.payload.as_bytes(), &signature)
+        .map_err(|_| "License response signature verification failed".to_string())?;
+    serde_json::from_str(&response.payload).map_err(|e| format!("Invalid license payload: {}", e))
+}
+
+fn new_nonce() -> String {
+    use aes_gcm::aead::{rand_core::RngCore, OsRng};
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// POST to {base_url}/{endpoint} and return the verified payload.
+fn call(config: &ApiConfig, endpoint: &str, license_key: &str) -> Result<LicensePayload, ApiError> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .https_only(true)
+        .build()
+        .map_err(|e| ApiError::Unavailable(format!("HTTP client error: {}", e)))?;
+    let request = LicenseRequest {
+        license_key: license_key.trim(),
+        machine_id: crate::license::generate_machine_id(),
+        nonce: new_nonce(),
+    };
+    let response = client
+        .post(format!("{}/{}", config.base_url, endpoint))
+        .json(&request)
+        .send()
+        .map_err(|e| ApiError::Unavailable(format!("License API unreachable: {}", e)))?;
+    if response.status().is_server_error() {
+        return Err(ApiError::Unavailable(format!("License API error: {}", response.status())));
+    }
+    if !response.status().is_success() {
+        return Err(ApiError::Rejected(format!("License API error: {}", response.status())));
+    }
+    let signed: SignedResponse = response
+        .json()
+        .map_err(|e| ApiError::Rejected(format!("Invalid license API response: {}", e)))?;
+
+    let payload = verify_signed_payload(&config.public_key, &signed).map_err(ApiError::Rejected)?;
+    if payload.nonce != request.nonce || payload.license_key != request.license_key {
+        return Err(ApiError::Rejected("License response does not match the request".to_string()));
+    }
+    Ok(payload)
+}
+
+/// Run REST mode when configured; fall back to the legacy MySQL server when not configured or unreachable.
+fn with_fallback<T>(
+    endpoint: &str,
+    license_key: &str,
+    rest: impl FnOnce(LicensePayload) -> T,
+    legacy: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    let config = match api_config()? {
+        Some(c) => c,
+        None => return legacy(),
+    };
+    match call(&config, endpoint, license_key) {
+        Ok(payload) => Ok(rest(payload)),
+        Err(ApiError::Unavailable(e)) => {
+            eprintln!("⚠️ {}; using legacy license server", e);
+            legacy()
+        }
+        Err(ApiError::Rejected(e)) => Err(e),
+    }
+}
+
+/// Check a license key: valid, expired or invalid.
+pub fn check_license(license_key: &str) -> Result<LicenseCheckResult, String> {
+    if license_key.trim().is_empty() {
+        return Ok(LicenseCheckResult {
+            valid: false,
+            reason: Some("invalid".to_string()),
+        });
+    }
+    with_fallback(
+        "check",
+        license_key,
+        |p| LicenseCheckResult {
+            valid: p.valid,
+            reason: if p.valid { None } else { p.reason.or_else(|| Some("invalid".to_string())) },
+        },
+        || license_server::check_license_against_server(license_key),
+    )
+}
+
+/// Fetch the license expiry (ISO datetime). None if the key is unknown.
+pub fn fetch_expiry_iso(license_key: &str) -> Result<Option<String>, String> {
+    if license_key.trim().is_empty() {
+        return Ok(None);
+    }
+    with_fallback(
+        "check",
+        license_key,
+        |p| p.expires_at,
+        || license_server::fetch_expiry_iso_from_server(license_key),
+    )
+}
+
+/// Register a new license key (first activation). Returns Some(expiry_iso) when the server created it.
+pub fn register_license(license_key: &str) -> Result<Option<String>, String> {
+    if license_key.trim().is_empty() {
+        return Err("License key is empty".to_string());
+    }
+    with_fallback(
+        "activate",
+        license_key,
+        |p| p.expires_at,
+        || license_server::insert_license_on_server(license_key),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    #[test]
+    fn test_signed_payload_verification() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = parse_public_key(&hex::encode(signing_key.verifying_key().to_bytes())).unwrap();
+        let payload = r#"{"license_key":"K","nonce":"n1","valid":true,"reason":null,"expires_at":"2030-01-01T00:00:00"}"#;
+        let signed = SignedResponse {
+            payload: payload.to_string(),
+            signature: hex::encode(signing_key.sign(payload.as_bytes()).to_bytes()),
+        };
+        let decoded = verify_signed_payload(&public_key, &signed).unwrap();
+        assert!(decoded.valid);
+        assert_eq!(decoded.nonce, "n1");
+
+        let tampered = SignedResponse {
+            payload: payload.replace("2030", "2099"),
+            signature: signed.signature.clone(),
+        };
+        assert!(verify_signed_payload(&public_key, &tampered).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 //! Remote MySQL license server: hardcoded config, DB/table setup, and license check.
+//! Legacy mode: used only when the signed HTTPS license API (license_api.rs) is not configured or unreachable.
 
 use chrono::{DateTime, TimeZone, Utc};
 use crate::license::{decrypt_expiry_datetime, encrypt_expiry_datetime};
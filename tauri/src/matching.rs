@@ -0,0 +1,140 @@
+/// Normalize a phone number to a canonical form so the same number typed differently compares equal.
+/// Afghan numbers become +93XXXXXXXXX (accepts 07XXXXXXXX, 7XXXXXXXX, 937XXXXXXXX, 0093..., +93 0...).
+/// Persian/Arabic digits are converted; separators are dropped. Other numbers keep their digits (and a leading +).
+pub fn normalize_phone(raw: &str) -> String {
+    let mut digits = String::new();
+    let mut plus = false;
+    for c in raw.trim().chars() {
+        let d = match c {
+            '0'..='9' => Some(c),
+            '۰'..='۹' => char::from_digit(c as u32 - '۰' as u32, 10),
+            '٠'..='٩' => char::from_digit(c as u32 - '٠' as u32, 10),
+            '+' if digits.is_empty() => {
+                plus = true;
+                None
+            }
+            _ => None,
+        };
+        if let Some(d) = d {
+            digits.push(d);
+        }
+    }
+    if digits.is_empty() {
+        return String::new();
+    }
+
+    if !plus && digits.starts_with("00") {
+        digits.drain(..2);
+        plus = true;
+    }
+
+    let national = if plus {
+        match digits.strip_prefix("93") {
+            Some(rest) => rest.trim_start_matches('0').to_string(),
+            None => return format!("+{}", digits),
+        }
+    } else if digits.len() == 11 && digits.starts_with("93") {
+        digits[2..].to_string()
+    } else if digits.len() == 10 && digits.starts_with('0') {
+        digits[1..].to_string()
+    } else if digits.len() == 9 && !digits.starts_with('0') {
+        digits.clone()
+    } else {
+        return digits;
+    };
+
+    format!("+93{}", national)
+}
+
+/// Digits-only phone key for prefix search: the national number for Afghan phones ("0799 12" → "79912"),
+/// the full international digits otherwise. Works on partial input, so it also turns a search term into a prefix.
+pub fn phone_search_key(raw: &str) -> String {
+    let normalized = normalize_phone(raw);
+    match normalized.strip_prefix("+93") {
+        Some(national) => national.to_string(),
+        None => normalized.trim_start_matches('+').trim_start_matches('0').to_string(),
+    }
+}
+
+/// Fold a person/company name for comparison: lowercase, unify Arabic/Persian letter variants,
+/// drop diacritics and collapse whitespace (ZWNJ counts as a space).
+pub fn normalize_name(raw: &str) -> String {
+    let folded: String = raw
+        .chars()
+        .filter_map(|c| match c {
+            'ي' | 'ى' => Some('ی'),
+            'ك' => Some('ک'),
+            'ة' => Some('ه'),
+            'أ' | 'إ' | 'آ' => Some('ا'),
+            '\u{064B}'..='\u{065F}' | '\u{0670}' => None,
+            '\u{200C}' => Some(' '),
+            _ => Some(c),
+        })
+        .flat_map(|c| c.to_lowercase())
+        .collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j + 1] + 1).min(cur[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+fn ratio(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let max_len = a.len().max(b.len());
+    if max_len == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(&a, &b) as f64 / max_len as f64
+}
+
+/// Similarity of two names in 0..=1 (edit distance on folded names; word order is ignored).
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let a = normalize_name(a);
+    let b = normalize_name(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let sorted = |s: &str| {
+        let mut words: Vec<&str> = s.split(' ').collect();
+        words.sort_unstable();
+        words.join(" ")
+    };
+    ratio(&a, &b).max(ratio(&sorted(&a), &sorted(&b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_afghan_phone_formats() {
+        for raw in ["0799 123 456", "799123456", "93799123456", "+93 799-123-456", "0093799123456", "+930799123456", "۰۷۹۹۱۲۳۴۵۶"] {
+            assert_eq!(normalize_phone(raw), "+93799123456", "input: {}", raw);
+        }
+        assert_eq!(normalize_phone("+49 30 123456"), "+4930123456");
+        assert_eq!(normalize_phone(""), "");
+        assert_eq!(phone_search_key("+93 799-123-456"), "799123456");
+        assert_eq!(phone_search_key("0799 12"), "79912");
+        assert_eq!(phone_search_key("+9379"), "79");
+    }
+
+    #[test]
+    fn test_name_similarity() {
+        assert_eq!(name_similarity("علي احمدي", "علی احمدی"), 1.0);
+        assert_eq!(name_similarity("Ahmad  Karimi", "karimi ahmad"), 1.0);
+        assert!(name_similarity("Ahmad Karimi", "Ahmed Karimi") > 0.85);
+        assert!(name_similarity("Ahmad Karimi", "Mahmood Rahimi") < 0.7);
+    }
+}
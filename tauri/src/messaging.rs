@@ -0,0 +1,237 @@
+//! Text messages to customers over an SMS or WhatsApp gateway: Twilio, or any HTTP gateway that takes a JSON post
+//! (`{"channel", "to", "from", "message"}` with a bearer token, answering `{"id", "status"}`). Numbers go out in
+//! international form and campaigns send no faster than the configured rate.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const CHANNELS: [&str; 2] = ["sms", "whatsapp"];
+pub const DEFAULT_COUNTRY_CODE: &str = "93";
+pub const DEFAULT_RATE_PER_MINUTE: u32 = 30;
+pub const MAX_RATE_PER_MINUTE: u32 = 600;
+const TWILIO_API: &str = "https://api.twilio.com/2010-04-01";
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Provider {
+    Twilio,
+    Http,
+}
+
+impl Provider {
+    pub fn parse(provider: &str) -> Result<Self, String> {
+        match provider.trim() {
+            "twilio" => Ok(Provider::Twilio),
+            "http" => Ok(Provider::Http),
+            other => Err(format!("Invalid gateway: {} (use twilio or http)", other)),
+        }
+    }
+}
+
+/// Gateway settings kept in app_settings; the auth token is in the secure store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayConfig {
+    pub provider: Provider,
+    /// Endpoint of an http gateway.
+    pub url: Option<String>,
+    /// Twilio account SID.
+    pub account: Option<String>,
+    pub sms_from: Option<String>,
+    pub whatsapp_from: Option<String>,
+    /// Added to local numbers that start with 0.
+    pub country_code: String,
+    pub rate_per_minute: u32,
+}
+
+impl GatewayConfig {
+    /// The settings as stored: an https url for http gateways, an account for Twilio, and a rate within the limits.
+    pub fn validated(mut self) -> Result<Self, String> {
+        match self.provider {
+            Provider::Http => {
+                let url = self.url.as_deref().map(str::trim).unwrap_or("").trim_end_matches('/').to_string();
+                if !url.starts_with("https://") {
+                    return Err("Gateway URL must start with https://".to_string());
+                }
+                self.url = Some(url);
+            }
+            Provider::Twilio => {
+                if self.account.as_deref().map(str::trim).unwrap_or("").is_empty() {
+                    return Err("Twilio account SID is required".to_string());
+                }
+            }
+        }
+        self.country_code = self.country_code.trim().trim_start_matches('+').to_string();
+        if self.country_code.is_empty() || self.country_code.len() > 3 || !self.country_code.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid country code: {}", self.country_code));
+        }
+        if !(1..=MAX_RATE_PER_MINUTE).contains(&self.rate_per_minute) {
+            return Err(format!("Send rate must be between 1 and {} messages a minute", MAX_RATE_PER_MINUTE));
+        }
+        Ok(self)
+    }
+
+    fn sender(&self, channel: &str) -> Option<&str> {
+        let from = if channel == "whatsapp" { &self.whatsapp_from } else { &self.sms_from };
+        from.as_deref().map(str::trim).filter(|f| !f.is_empty())
+    }
+}
+
+/// A phone number in international form ("+937..."): local numbers starting with 0 get `country_code`, "00" becomes
+/// "+". None when it is not 8 to 15 digits after that.
+pub fn normalize_phone(phone: &str, country_code: &str) -> Option<String> {
+    let compact: String = phone.chars().filter(|c| !c.is_whitespace() && !matches!(c, '-' | '(' | ')' | '.')).collect();
+    let digits = if let Some(rest) = compact.strip_prefix('+') {
+        rest.to_string()
+    } else if let Some(rest) = compact.strip_prefix("00") {
+        rest.to_string()
+    } else if let Some(rest) = compact.strip_prefix('0') {
+        format!("{}{}", country_code, rest)
+    } else {
+        compact
+    };
+    (digits.chars().all(|c| c.is_ascii_digit()) && (8..=15).contains(&digits.len())).then(|| format!("+{}", digits))
+}
+
+/// Pause between two messages so a campaign keeps to `rate_per_minute`.
+pub fn send_interval(rate_per_minute: u32) -> Duration {
+    Duration::from_millis(60_000 / rate_per_minute.clamp(1, MAX_RATE_PER_MINUTE) as u64)
+}
+
+/// A gateway's message status as ours: sent (accepted, on its way), delivered or failed.
+pub fn delivery_status(provider_status: &str) -> &'static str {
+    match provider_status.trim().to_ascii_lowercase().as_str() {
+        "delivered" | "read" => "delivered",
+        "failed" | "undelivered" | "canceled" | "cancelled" | "rejected" | "expired" => "failed",
+        _ => "sent",
+    }
+}
+
+pub struct SentMessage {
+    pub id: String,
+    pub status: &'static str,
+}
+
+/// A failed send; `retry` when the gateway is busy or unreachable, so the message should stay queued.
+#[derive(Debug)]
+pub struct SendError {
+    pub message: String,
+    pub retry: bool,
+}
+
+#[derive(Deserialize)]
+struct MessageResponse {
+    #[serde(alias = "sid")]
+    id: Option<String>,
+    #[serde(default)]
+    status: String,
+}
+
+pub struct Gateway {
+    config: GatewayConfig,
+    token: String,
+    http: reqwest::blocking::Client,
+}
+
+impl Gateway {
+    pub fn new(config: GatewayConfig, token: String) -> Result<Self, String> {
+        let http = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| format!("HTTP client error: {}", e))?;
+        Ok(Gateway { config, token, http })
+    }
+
+    fn account(&self) -> &str {
+        self.config.account.as_deref().unwrap_or("").trim()
+    }
+
+    fn authorize(&self, request: reqwest::blocking::RequestBuilder) -> reqwest::blocking::RequestBuilder {
+        match self.config.provider {
+            Provider::Twilio => request.basic_auth(self.account(), Some(&self.token)),
+            Provider::Http => request.bearer_auth(&self.token),
+        }
+    }
+
+    fn call(&self, request: reqwest::blocking::RequestBuilder) -> Result<MessageResponse, SendError> {
+        let response = self
+            .authorize(request)
+            .send()
+            .map_err(|e| SendError { message: format!("Gateway request failed: {}", e), retry: true })?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(SendError {
+                message: format!("Gateway error {}: {}", status, body.chars().take(200).collect::<String>()),
+                retry: status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error(),
+            });
+        }
+        response.json().map_err(|e| SendError { message: format!("Invalid gateway response: {}", e), retry: false })
+    }
+
+    /// Hand one message to the gateway; `to` is an international number.
+    pub fn send(&self, channel: &str, to: &str, message: &str) -> Result<SentMessage, SendError> {
+        let from = self.config.sender(channel).map(str::to_string);
+        let request = match self.config.provider {
+            Provider::Twilio => {
+                let from = from.ok_or_else(|| SendError { message: format!("No {} sender number is configured", channel), retry: false })?;
+                let (to, from) = if channel == "whatsapp" { (format!("whatsapp:{}", to), format!("whatsapp:{}", from)) } else { (to.to_string(), from) };
+                self.http
+                    .post(format!("{}/Accounts/{}/Messages.json", TWILIO_API, self.account()))
+                    .form(&[("To", to.as_str()), ("From", from.as_str()), ("Body", message)])
+            }
+            Provider::Http => self.http.post(self.config.url.as_deref().unwrap_or("")).json(&serde_json::json!({
+                "channel": channel,
+                "to": to,
+                "from": from,
+                "message": message,
+            })),
+        };
+        let response = self.call(request)?;
+        let id = response.id.filter(|id| !id.is_empty()).ok_or_else(|| SendError { message: "Gateway returned no message id".to_string(), retry: false })?;
+        Ok(SentMessage { id, status: delivery_status(&response.status) })
+    }
+
+    /// Current delivery status of a sent message.
+    pub fn status(&self, message_id: &str) -> Result<&'static str, String> {
+        let url = match self.config.provider {
+            Provider::Twilio => format!("{}/Accounts/{}/Messages/{}.json", TWILIO_API, self.account(), message_id),
+            Provider::Http => format!("{}/{}", self.config.url.as_deref().unwrap_or(""), message_id),
+        };
+        self.call(self.http.get(url)).map(|r| delivery_status(&r.status)).map_err(|e| e.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messaging_rules() {
+        assert_eq!(normalize_phone("0799 123 456", "93"), Some("+93799123456".to_string()));
+        assert_eq!(normalize_phone("+93 (799) 123-456", "93"), Some("+93799123456".to_string()));
+        assert_eq!(normalize_phone("0093799123456", "93"), Some("+93799123456".to_string()));
+        assert_eq!(normalize_phone("799-12", "93"), None);
+        assert_eq!(normalize_phone("07991234ab", "93"), None);
+        assert_eq!(send_interval(30), Duration::from_secs(2));
+        assert_eq!(send_interval(0), Duration::from_secs(60));
+        assert_eq!(delivery_status("queued"), "sent");
+        assert_eq!(delivery_status("Delivered"), "delivered");
+        assert_eq!(delivery_status("undelivered"), "failed");
+
+        let config = GatewayConfig {
+            provider: Provider::Http,
+            url: Some(" https://sms.example.af/api/ ".to_string()),
+            account: None,
+            sms_from: None,
+            whatsapp_from: None,
+            country_code: "+93".to_string(),
+            rate_per_minute: 60,
+        };
+        let config = config.validated().unwrap();
+        assert_eq!(config.url.as_deref(), Some("https://sms.example.af/api"));
+        assert_eq!(config.country_code, "93");
+        assert!(GatewayConfig { rate_per_minute: 0, ..config.clone() }.validated().is_err());
+        assert!(GatewayConfig { provider: Provider::Twilio, ..config }.validated().is_err());
+    }
+}
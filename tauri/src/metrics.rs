@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Process-wide counters exposed on /metrics in Prometheus text format (enable with METRICS_ENABLED=true in .env).
+static SALES_CREATED: AtomicU64 = AtomicU64::new(0);
+static DB_ERRORS: AtomicU64 = AtomicU64::new(0);
+static BACKUPS_SUCCEEDED: AtomicU64 = AtomicU64::new(0);
+static BACKUPS_FAILED: AtomicU64 = AtomicU64::new(0);
+static LAST_BACKUP_SUCCESS_UNIX: AtomicU64 = AtomicU64::new(0);
+
+/// Upper bounds (seconds) of the command latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 10.0];
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+static COMMAND_LATENCY: Mutex<BTreeMap<&'static str, Histogram>> = Mutex::new(BTreeMap::new());
+
+/// Whether the /metrics endpoint should be served.
+pub fn enabled() -> bool {
+    std::env::var("METRICS_ENABLED")
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+pub fn inc_sales_created() {
+    SALES_CREATED.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn inc_db_errors() {
+    DB_ERRORS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record the outcome of a backup run (manual or automatic).
+pub fn record_backup(success: bool) {
+    if success {
+        BACKUPS_SUCCEEDED.fetch_add(1, Ordering::Relaxed);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        LAST_BACKUP_SUCCESS_UNIX.store(now, Ordering::Relaxed);
+    } else {
+        BACKUPS_FAILED.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+pub fn observe_command(command: &'static str, seconds: f64) {
+    let mut map = COMMAND_LATENCY.lock().unwrap_or_else(|e| e.into_inner());
+    let h = map.entry(command).or_default();
+    for (i, bound) in LATENCY_BUCKETS.iter().enumerate() {
+        if seconds <= *bound {
+            h.buckets[i] += 1;
+        }
+    }
+    h.count += 1;
+    h.sum += seconds;
+}
+
+/// Records the command's latency when dropped: `let _timer = metrics::CommandTimer::start("create_sale");`
+pub struct CommandTimer {
+    command: &'static str,
+    started: Instant,
+}
+
+impl CommandTimer {
+    pub fn start(command: &'static str) -> Self {
+        CommandTimer { command, started: Instant::now() }
+    }
+}
+
+impl Drop for CommandTimer {
+    fn drop(&mut self) {
+        observe_command(self.command, self.started.elapsed().as_secs_f64());
+    }
+}
+
+/// Render all metrics in Prometheus text exposition format.
+pub fn render() -> String {
+    let mut out = String::new();
+    let counters = [
+        ("shafaf_sales_created_total", "Sales created since start", &SALES_CREATED),
+        ("shafaf_db_errors_total", "Database errors since start", &DB_ERRORS),
+        ("shafaf_backups_succeeded_total", "Successful backups since start", &BACKUPS_SUCCEEDED),
+        ("shafaf_backups_failed_total", "Failed backups since start", &BACKUPS_FAILED),
+    ];
+    for (name, help, value) in counters {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} counter\n{} {}", name, help, name, name, value.load(Ordering::Relaxed));
+    }
+    let _ = writeln!(
+        out,
+        "# HELP shafaf_last_backup_success_timestamp_seconds Unix time of the last successful backup (0 = none)\n# TYPE shafaf_last_backup_success_timestamp_seconds gauge\nshafaf_last_backup_success_timestamp_seconds {}",
+        LAST_BACKUP_SUCCESS_UNIX.load(Ordering::Relaxed)
+    );
+
+    let map = COMMAND_LATENCY.lock().unwrap_or_else(|e| e.into_inner());
+    let _ = writeln!(
+        out,
+        "# HELP shafaf_command_duration_seconds Backend command latency\n# TYPE shafaf_command_duration_seconds histogram"
+    );
+    for (command, h) in map.iter() {
+        for (i, bound) in LATENCY_BUCKETS.iter().enumerate() {
+            let _ = writeln!(out, "shafaf_command_duration_seconds_bucket{{command=\"{}\",le=\"{}\"}} {}", command, bound, h.buckets[i]);
+        }
+        let _ = writeln!(out, "shafaf_command_duration_seconds_bucket{{command=\"{}\",le=\"+Inf\"}} {}", command, h.count);
+        let _ = writeln!(out, "shafaf_command_duration_seconds_sum{{command=\"{}\"}} {}", command, h.sum);
+        let _ = writeln!(out, "shafaf_command_duration_seconds_count{{command=\"{}\"}} {}", command, h.count);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_includes_counters_and_histogram() {
+        inc_sales_created();
+        observe_command("test_command", 0.02);
+        let text = render();
+        assert!(text.contains("# TYPE shafaf_sales_created_total counter"));
+        assert!(text.contains("shafaf_command_duration_seconds_bucket{command=\"test_command\",le=\"0.025\"} 1"));
+        assert!(text.contains("shafaf_command_duration_seconds_bucket{command=\"test_command\",le=\"0.01\"} 0"));
+        assert!(text.contains("shafaf_command_duration_seconds_count{command=\"test_command\"} 1"));
+    }
+}
@@ -0,0 +1,87 @@
+//! Amount formatting shared by reports, receipts and documents: decimal places per currency, thousands and decimal
+//! separators and the currency symbol before or after the number.
+
+/// How amounts of one currency are written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrencyFormat {
+    pub decimals: u32,
+    /// Written next to the number; empty for none.
+    pub symbol: String,
+    pub symbol_after: bool,
+    pub thousands_separator: String,
+    pub decimal_separator: String,
+}
+
+impl Default for CurrencyFormat {
+    fn default() -> Self {
+        CurrencyFormat {
+            decimals: 2,
+            symbol: String::new(),
+            symbol_after: true,
+            thousands_separator: ",".to_string(),
+            decimal_separator: ".".to_string(),
+        }
+    }
+}
+
+/// Decimal places for a currency without its own setting: 0 for currencies whose smallest coin is out of use
+/// (afghani, toman, rial), 2 otherwise.
+pub fn default_decimals(currency: &str) -> u32 {
+    const WHOLE: [&str; 7] = ["افغانی", "afn", "afghani", "تومان", "toman", "ریال", "irr"];
+    let name = currency.trim().to_lowercase();
+    if WHOLE.contains(&name.as_str()) {
+        0
+    } else {
+        2
+    }
+}
+
+/// `value` rounded to the currency's decimals with grouped thousands; with `with_symbol`, the symbol goes before
+/// or after the number (the minus sign always leads).
+pub fn format_amount(value: f64, fmt: &CurrencyFormat, with_symbol: bool) -> String {
+    let decimals = fmt.decimals.min(6);
+    let factor = 10u64.pow(decimals);
+    // The nudge keeps values like 1.005 from rounding down through their binary representation
+    let scaled = (value.abs() * factor as f64 + 1e-6).round() as u64;
+    let (whole, frac) = (scaled / factor, scaled % factor);
+
+    let digits = whole.to_string();
+    let mut number = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            number.push_str(&fmt.thousands_separator);
+        }
+        number.push(c);
+    }
+    if decimals > 0 {
+        number.push_str(&fmt.decimal_separator);
+        number.push_str(&format!("{:0width$}", frac, width = decimals as usize));
+    }
+
+    let sign = if value < 0.0 && scaled > 0 { "-" } else { "" };
+    match (with_symbol && !fmt.symbol.is_empty(), fmt.symbol_after) {
+        (false, _) => format!("{}{}", sign, number),
+        (true, true) => format!("{}{} {}", sign, number, fmt.symbol),
+        (true, false) => format!("{}{}{}", sign, fmt.symbol, number),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_amount() {
+        let afn = CurrencyFormat { decimals: default_decimals("افغانی"), symbol: "؋".to_string(), ..Default::default() };
+        assert_eq!(format_amount(1234567.5, &afn, true), "1,234,568 ؋");
+        assert_eq!(format_amount(999.0, &afn, false), "999");
+
+        let usd = CurrencyFormat { decimals: default_decimals("USD"), symbol: "$".to_string(), symbol_after: false, ..Default::default() };
+        assert_eq!(format_amount(1234.5, &usd, true), "$1,234.50");
+        assert_eq!(format_amount(-1.005, &usd, true), "-$1.01");
+        assert_eq!(format_amount(-0.001, &usd, false), "0.00");
+
+        let eu = CurrencyFormat { thousands_separator: ".".to_string(), decimal_separator: ",".to_string(), ..Default::default() };
+        assert_eq!(format_amount(1000000.0, &eu, true), "1.000.000,00");
+    }
+}
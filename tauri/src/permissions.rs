@@ -0,0 +1,285 @@
+//! Per-command permissions: which commands a session may invoke, from rules per role and per user over the defaults of
+//! each role; a command nothing allows is denied. A rule names a command or, with a trailing '*', every command
+//! starting with the text before it ("delete_*").
+
+/// Roles the app knows about; other role names can be assigned too.
+pub const ROLES: [&str; 4] = ["admin", "user", "salesperson", "cashier"];
+
+/// Commands callable without a session: opening the database, signing in, activating a license and license checks
+/// shown before login.
+const PUBLIC_COMMANDS: [&str; 23] = [
+    "db_create",
+    "db_open",
+    "db_close",
+    "db_is_open",
+    "db_pool_status",
+    "get_database_path",
+    "get_db_connection_state",
+    "login_user",
+    "logout_user",
+    "get_current_user",
+    "get_company_settings",
+    "check_clock_integrity",
+    "get_secure_store_status",
+    "store_license_key",
+    "get_license_key",
+    "get_license_expiry",
+    "validate_license_key",
+    "check_license_with_server",
+    "check_license_key_with_server",
+    "register_license_on_server",
+    "refresh_license_expiry_from_server",
+    "get_license_status",
+    "get_machine_id",
+];
+
+/// Commands callable without a session only while no database is open, so a terminal that cannot reach its server
+/// can be pointed at one.
+const SETUP_COMMANDS: [&str; 2] = ["get_env_config", "save_env_config"];
+
+/// Commands only admins may run unless a rule allows them for a role or user; the role defaults below never include
+/// them.
+const ADMIN_ONLY_COMMANDS: [&str; 42] = [
+    "db_execute",
+    "restore_database",
+    "rollback_last_restore",
+    "puter_restore_backup",
+    "restore_backup_chain",
+    "delete_purchase",
+    "delete_sale",
+    "set_user_role",
+    "set_permission",
+    "delete_permission",
+    "sql_console_run",
+    "assign_cash_drawer",
+    "release_cash_drawer",
+    "get_cash_drawer_balance",
+    "export_all_data",
+    "import_all_data",
+    "set_storefront_connection",
+    "set_messaging_gateway",
+    "reopen_period",
+    "set_scoped_setting",
+    "set_app_setting",
+    "register_user",
+    "get_env_config",
+    "save_env_config",
+    "get_license_key",
+    "create_api_token",
+    "revoke_api_token",
+    "get_api_tokens",
+    "get_audit_log",
+    "migrate_field_encryption",
+    "migrate_secure_store",
+    "migrate_existing_data",
+    "generate_demo_data",
+    "enter_training_mode",
+    "leave_training_mode",
+    "anonymize_customer",
+    "merge_customers",
+    "merge_suppliers",
+    "assign_customer_to_user",
+    "finalize_period",
+    "store_puter_credentials",
+    "get_puter_credentials",
+];
+
+/// Commands every signed-in role may run: reading data, table setup, printing and its own session and PIN.
+const SIGNED_IN_COMMANDS: [&str; 21] = [
+    "get_*",
+    "list_*",
+    "search_*",
+    "find_*",
+    "check_*",
+    "preview_*",
+    "validate_*",
+    "verify_*",
+    "format_*",
+    "trace_*",
+    "print_*",
+    "init_*",
+    "db_query",
+    "quote_shipping_cost",
+    "queue_print_job",
+    "retry_print_job",
+    "lock_session",
+    "unlock_with_pin",
+    "set_user_pin",
+    "clear_user_pin",
+    "update_user_profile_picture",
+];
+
+/// Cashiers also take sales and payments, run restaurant tickets and count their drawer.
+const CASHIER_COMMANDS: [&str; 13] = [
+    "create_sale",
+    "create_sale_payment",
+    "add_bundle_to_sale",
+    "create_customer_quick",
+    "open_order_ticket",
+    "add_order_ticket_items",
+    "update_order_item_status",
+    "send_ticket_to_kitchen",
+    "close_order_ticket",
+    "record_cash_drop",
+    "record_drawer_count",
+    "return_containers",
+    "create_backorder",
+];
+
+/// Salespeople also look after their customers: details, collections, returns and backorders.
+const SALESPERSON_COMMANDS: [&str; 9] = [
+    "create_customer",
+    "update_customer",
+    "set_customer_tags",
+    "set_customer_language",
+    "record_collection_contact",
+    "create_sale_return",
+    "cancel_backorder",
+    "fulfill_backorders",
+    "generate_share_link",
+];
+
+/// Users (back-office staff) also keep the books and the stock: creating, changing and deleting records, and the
+/// day-to-day operations on them.
+const USER_COMMANDS: [&str; 33] = [
+    "create_*",
+    "update_*",
+    "delete_*",
+    "add_*",
+    "remove_*",
+    "record_*",
+    "cancel_*",
+    "close_*",
+    "set_product_*",
+    "set_purchase_*",
+    "set_customer_*",
+    "deposit_account",
+    "withdraw_account",
+    "exchange_between_currencies",
+    "reconcile_account_balance",
+    "adjust_purchase_costs",
+    "adjust_due_date",
+    "link_expense_to_asset",
+    "post_monthly_depreciation",
+    "import_branch_transfer",
+    "return_containers",
+    "open_order_ticket",
+    "send_ticket_to_kitchen",
+    "fulfill_backorders",
+    "take_stock_snapshot",
+    "generate_product_labels",
+    "generate_share_link",
+    "revoke_share_link",
+    "export_journal",
+    "export_customer_data",
+    "pull_storefront_orders",
+    "accept_storefront_order",
+    "reject_storefront_order",
+];
+
+/// A stored rule as it applies to one session: `user_level` rules were set for the user, the others for their role.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub command: String,
+    pub allowed: bool,
+    pub user_level: bool,
+}
+
+pub fn is_public(command: &str) -> bool {
+    PUBLIC_COMMANDS.contains(&command)
+}
+
+pub fn is_setup(command: &str) -> bool {
+    SETUP_COMMANDS.contains(&command)
+}
+
+/// Whether `role` may run `command` when no rule decides: never for the admin-only commands, else when the role's
+/// defaults list it. Roles other than the built-in ones only read.
+fn role_default(role: &str, command: &str) -> bool {
+    if ADMIN_ONLY_COMMANDS.contains(&command) {
+        return false;
+    }
+    let role_commands: &[&[&str]] = match role {
+        "user" => &[&USER_COMMANDS[..]],
+        "salesperson" => &[&SALESPERSON_COMMANDS[..], &CASHIER_COMMANDS[..]],
+        "cashier" => &[&CASHIER_COMMANDS[..]],
+        _ => &[],
+    };
+    SIGNED_IN_COMMANDS
+        .iter()
+        .chain(role_commands.iter().flat_map(|c| c.iter()))
+        .any(|p| specificity(p, command).is_some())
+}
+
+/// Whether `pattern` is a command name or a prefix ending in '*'.
+pub fn valid_pattern(pattern: &str) -> bool {
+    let name = pattern.strip_suffix('*').unwrap_or(pattern);
+    (!name.is_empty() || pattern == "*") && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// How closely `pattern` matches `command`: None when it does not, else higher for more specific patterns.
+fn specificity(pattern: &str, command: &str) -> Option<usize> {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => command.starts_with(prefix).then_some(prefix.len()),
+        None => (pattern == command).then_some(usize::MAX),
+    }
+}
+
+/// Whether a session with `role` and `rules` may run `command`. Admins always may, so they cannot lock themselves
+/// out. Otherwise the most specific user rule decides, then the most specific role rule, then the role's defaults:
+/// anything not listed for the role is denied.
+pub fn allowed(role: &str, rules: &[Rule], command: &str) -> bool {
+    if role == "admin" {
+        return true;
+    }
+    for user_level in [true, false] {
+        let best = rules
+            .iter()
+            .filter(|r| r.user_level == user_level)
+            .filter_map(|r| specificity(&r.command, command).map(|s| (s, r.allowed)))
+            .max_by_key(|(s, allowed)| (*s, !*allowed));
+        if let Some((_, allowed)) = best {
+            return allowed;
+        }
+    }
+    role_default(role, command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(command: &str, allowed: bool, user_level: bool) -> Rule {
+        Rule { command: command.to_string(), allowed, user_level }
+    }
+
+    #[test]
+    fn test_allowed() {
+        assert!(is_public("login_user"));
+        assert!(!is_public("init_sales_table"));
+        assert!(!is_public("create_sale"));
+        assert!(!is_public("save_env_config") && is_setup("save_env_config"));
+        assert!(!is_public("register_user") && !is_public("store_license_expiry"));
+        assert!(allowed("admin", &[rule("*", false, true)], "restore_database"));
+        assert!(allowed("user", &[], "create_sale"));
+        assert!(!allowed("user", &[], "delete_purchase"));
+        assert!(!allowed("user", &[], "set_app_setting"));
+        assert!(!allowed("user", &[], "create_api_token"));
+        assert!(!allowed("user", &[], "merge_customers"));
+        assert!(allowed("cashier", &[], "get_sales") && allowed("cashier", &[], "create_sale"));
+        assert!(!allowed("cashier", &[], "create_product"));
+        assert!(!allowed("cashier", &[], "finalize_period"));
+        assert!(allowed("salesperson", &[], "create_sale") && allowed("salesperson", &[], "update_customer"));
+        assert!(!allowed("salesperson", &[], "delete_customer"));
+        assert!(allowed("auditor", &[], "get_sales") && !allowed("auditor", &[], "create_sale"));
+        let rules = [rule("delete_*", false, false), rule("delete_sale", true, false), rule("delete_sale_item", false, true)];
+        assert!(!allowed("cashier", &rules, "delete_customer"));
+        assert!(allowed("cashier", &rules, "delete_sale"));
+        assert!(!allowed("cashier", &rules, "delete_sale_item"));
+        assert!(allowed("cashier", &rules, "create_sale"));
+        assert!(valid_pattern("delete_*"));
+        assert!(valid_pattern("*"));
+        assert!(!valid_pattern("Delete sale"));
+        assert!(!valid_pattern(""));
+    }
+}
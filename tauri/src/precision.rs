@@ -0,0 +1,91 @@
+//! Rounding precision for money and quantities: defaults from the settings, overridden per currency (money) and per
+//! unit group (quantities). Loaded when a database is opened and whenever one of them changes.
+
+use std::sync::RwLock;
+
+pub const DEFAULT_MONEY_DECIMALS: u32 = 2;
+pub const DEFAULT_QUANTITY_DECIMALS: u32 = 6;
+/// Highest precision that can be configured; f64 keeps about 15 significant digits.
+pub const MAX_DECIMALS: u32 = 8;
+
+struct Tables {
+    money: u32,
+    quantity: u32,
+    /// (currency_id, decimals) for currencies with their own money precision.
+    currencies: Vec<(i64, u32)>,
+    /// (unit_id, decimals) for units whose group has its own quantity precision.
+    units: Vec<(i64, u32)>,
+}
+
+static TABLES: RwLock<Tables> = RwLock::new(Tables {
+    money: DEFAULT_MONEY_DECIMALS,
+    quantity: DEFAULT_QUANTITY_DECIMALS,
+    currencies: Vec::new(),
+    units: Vec::new(),
+});
+
+/// Replace the configured precision.
+pub fn set(money: u32, quantity: u32, currencies: Vec<(i64, u32)>, units: Vec<(i64, u32)>) {
+    let clamp = |d: u32| d.min(MAX_DECIMALS);
+    if let Ok(mut t) = TABLES.write() {
+        *t = Tables {
+            money: clamp(money),
+            quantity: clamp(quantity),
+            currencies: currencies.into_iter().map(|(id, d)| (id, clamp(d))).collect(),
+            units: units.into_iter().map(|(id, d)| (id, clamp(d))).collect(),
+        };
+    }
+}
+
+/// Round half away from zero to `decimals` places.
+pub fn round_to(x: f64, decimals: u32) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    (x * factor).round() / factor
+}
+
+impl Tables {
+    fn money_decimals(&self, currency_id: Option<i64>) -> u32 {
+        currency_id.and_then(|id| self.currencies.iter().find(|c| c.0 == id)).map(|c| c.1).unwrap_or(self.money)
+    }
+
+    fn quantity_decimals(&self, unit_id: Option<i64>) -> u32 {
+        unit_id.and_then(|id| self.units.iter().find(|u| u.0 == id)).map(|u| u.1).unwrap_or(self.quantity)
+    }
+}
+
+/// Decimals for money in `currency_id` (the default money precision for None or a currency without its own).
+pub fn money_decimals(currency_id: Option<i64>) -> u32 {
+    TABLES.read().map(|t| t.money_decimals(currency_id)).unwrap_or(DEFAULT_MONEY_DECIMALS)
+}
+
+/// Decimals for quantities of `unit_id` (the default quantity precision for None or a unit without its own).
+pub fn quantity_decimals(unit_id: Option<i64>) -> u32 {
+    TABLES.read().map(|t| t.quantity_decimals(unit_id)).unwrap_or(DEFAULT_QUANTITY_DECIMALS)
+}
+
+pub fn quantity(x: f64) -> f64 {
+    round_to(x, quantity_decimals(None))
+}
+
+pub fn quantity_in(unit_id: i64, x: f64) -> f64 {
+    round_to(x, quantity_decimals(Some(unit_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precision() {
+        assert_eq!(round_to(10.005, 2), 10.01);
+        assert_eq!(round_to(1.0 / 3.0, 6), 0.333333);
+        assert_eq!(round_to(1234.5, 0), 1235.0);
+        assert_eq!(round_to(-2.5, 0), -3.0);
+        let t = Tables { money: 3, quantity: 2, currencies: vec![(7, 0)], units: vec![(4, 0)] };
+        assert_eq!(t.money_decimals(None), 3);
+        assert_eq!(t.money_decimals(Some(7)), 0);
+        assert_eq!(t.money_decimals(Some(8)), 3);
+        assert_eq!(t.quantity_decimals(Some(4)), 0);
+        assert_eq!(t.quantity_decimals(Some(5)), 2);
+    }
+}
@@ -0,0 +1,45 @@
+//! Price suggestions from cost: markup percentages and the rounding rules applied to suggested prices.
+
+/// Rounding modes accepted by pricing rules.
+pub const ROUNDING_MODES: [&str; 3] = ["nearest", "up", "down"];
+
+/// cost × (1 + markup% / 100).
+pub fn apply_markup(cost: f64, markup_percent: f64) -> f64 {
+    cost * (1.0 + markup_percent / 100.0)
+}
+
+/// Round to a multiple of `step` ("nearest", "up" or "down"); a step of 0 only rounds to 2 decimals.
+pub fn round_price(value: f64, step: f64, mode: &str) -> f64 {
+    if step <= 0.0 {
+        return (value * 100.0).round() / 100.0;
+    }
+    let units = value / step;
+    // Tolerance keeps 10.0 / 0.1 = 100.00000000000001 from rounding up to 101 steps
+    let n = match mode {
+        "up" => (units - 1e-9).ceil(),
+        "down" => (units + 1e-9).floor(),
+        _ => units.round(),
+    };
+    (n * step * 1_000_000.0).round() / 1_000_000.0
+}
+
+/// Suggested price for a cost under a markup and rounding rule.
+pub fn suggest(cost: f64, markup_percent: f64, step: f64, mode: &str) -> f64 {
+    round_price(apply_markup(cost, markup_percent), step, mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markup_and_rounding() {
+        assert_eq!(suggest(80.0, 25.0, 0.0, "nearest"), 100.0);
+        assert_eq!(suggest(83.0, 20.0, 5.0, "up"), 100.0);
+        assert_eq!(suggest(83.0, 20.0, 5.0, "down"), 95.0);
+        assert_eq!(suggest(83.0, 20.0, 5.0, "nearest"), 100.0);
+        assert_eq!(round_price(10.0, 0.1, "up"), 10.0);
+        assert_eq!(round_price(12.345, 0.25, "nearest"), 12.25);
+        assert_eq!(round_price(12.344, 0.0, "up"), 12.34);
+    }
+}
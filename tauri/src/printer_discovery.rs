@@ -0,0 +1,153 @@
+//! Finding ESC/POS printers without manual setup: raw-TCP (port 9100) scan of the local /24, mDNS
+//! `_pdl-datastream._tcp` responders and Linux USB printer devices. Each candidate is checked with a DLE EOT status query.
+
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, TcpStream, UdpSocket};
+use std::time::{Duration, Instant};
+
+/// DLE EOT 1: transmit printer status.
+pub const STATUS_QUERY: [u8; 3] = [0x10, 0x04, 0x01];
+
+/// Interpret a DLE EOT 1 reply: `Some(online)` for a well-formed status byte, `None` otherwise.
+pub fn parse_status(byte: u8) -> Option<bool> {
+    // Bits 1 and 4 are always set, bits 0 and 7 always clear; bit 3 means offline
+    (byte & 0x93 == 0x12).then_some(byte & 0x08 == 0)
+}
+
+/// Other host addresses of the /24 network `local` is on.
+pub fn subnet_hosts(local: Ipv4Addr) -> Vec<Ipv4Addr> {
+    let [a, b, c, d] = local.octets();
+    (1..=254u8).filter(|h| *h != d).map(|h| Ipv4Addr::new(a, b, c, h)).collect()
+}
+
+/// DNS query packet asking for PTR records of `service` (e.g. "_pdl-datastream._tcp.local") with unicast replies.
+pub fn mdns_query(service: &str) -> Vec<u8> {
+    let mut packet = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    for label in service.split('.').filter(|l| !l.is_empty()) {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.extend_from_slice(&[0, 0, 12, 0x80, 1]);
+    packet
+}
+
+/// Address of the interface used for outgoing traffic (no packet is sent).
+pub fn local_ipv4() -> Option<Ipv4Addr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    match socket.local_addr().ok()? {
+        SocketAddr::V4(addr) if !addr.ip().is_loopback() => Some(*addr.ip()),
+        _ => None,
+    }
+}
+
+/// Connect to `ip:port` and send a status query. `None` if nothing listens; `Some(status)` otherwise, where status
+/// is the parsed reply (`None` when the printer did not answer the query).
+pub fn probe(ip: Ipv4Addr, port: u16, timeout: Duration) -> Option<Option<bool>> {
+    let addr = SocketAddr::V4(SocketAddrV4::new(ip, port));
+    let mut stream = TcpStream::connect_timeout(&addr, timeout).ok()?;
+    let _ = stream.set_read_timeout(Some(timeout));
+    let _ = stream.set_write_timeout(Some(timeout));
+    if stream.write_all(&STATUS_QUERY).is_err() {
+        return Some(None);
+    }
+    let mut reply = [0u8; 1];
+    Some(match stream.read(&mut reply) {
+        Ok(1) => parse_status(reply[0]),
+        _ => None,
+    })
+}
+
+/// Probe every host of the local /24 on `port`, `parallel` connections at a time.
+pub fn scan_subnet(port: u16, timeout: Duration, parallel: usize) -> Vec<(Ipv4Addr, Option<bool>)> {
+    let Some(local) = local_ipv4() else { return Vec::new() };
+    let mut found = Vec::new();
+    for chunk in subnet_hosts(local).chunks(parallel.max(1)) {
+        let handles: Vec<_> = chunk
+            .iter()
+            .map(|ip| {
+                let ip = *ip;
+                std::thread::spawn(move || probe(ip, port, timeout).map(|status| (ip, status)))
+            })
+            .collect();
+        found.extend(handles.into_iter().filter_map(|h| h.join().ok().flatten()));
+    }
+    found
+}
+
+/// Hosts that answer an mDNS query for raw-port printers within `wait`.
+pub fn mdns_responders(wait: Duration) -> Vec<Ipv4Addr> {
+    let Ok(socket) = UdpSocket::bind("0.0.0.0:0") else { return Vec::new() };
+    let query = mdns_query("_pdl-datastream._tcp.local");
+    if socket.send_to(&query, "224.0.0.251:5353").is_err() {
+        return Vec::new();
+    }
+    let deadline = Instant::now() + wait;
+    let mut hosts = Vec::new();
+    let mut buf = [0u8; 1500];
+    while let Some(left) = deadline.checked_duration_since(Instant::now()).filter(|d| !d.is_zero()) {
+        let _ = socket.set_read_timeout(Some(left));
+        match socket.recv_from(&mut buf) {
+            Ok((_, SocketAddr::V4(from))) if !hosts.contains(from.ip()) => hosts.push(*from.ip()),
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+    hosts
+}
+
+/// USB printer device nodes (Linux `usblp` driver). Other platforms have none here.
+pub fn usb_devices() -> Vec<String> {
+    let mut devices: Vec<String> = std::fs::read_dir("/dev/usb")
+        .map(|dir| {
+            dir.filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("lp")))
+                .map(|p| p.to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    devices.sort();
+    devices
+}
+
+/// Open a USB printer device and send a status query. `None` if the device cannot be opened for writing; otherwise
+/// the parsed reply, `None` when no reply arrives within `timeout` (many USB printers do not answer).
+pub fn probe_device(path: &str, timeout: Duration) -> Option<Option<bool>> {
+    let mut device = std::fs::OpenOptions::new().read(true).write(true).open(path).ok()?;
+    if device.write_all(&STATUS_QUERY).is_err() {
+        return None;
+    }
+    let (tx, rx) = std::sync::mpsc::channel();
+    // Reads on printer devices can block indefinitely, so wait for the reply off-thread
+    std::thread::spawn(move || {
+        let mut reply = [0u8; 1];
+        if let Ok(1) = device.read(&mut reply) {
+            let _ = tx.send(reply[0]);
+        }
+    });
+    Some(rx.recv_timeout(timeout).ok().and_then(parse_status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_and_packets() {
+        assert_eq!(parse_status(0x16), Some(true));
+        assert_eq!(parse_status(0x1E), Some(false));
+        assert_eq!(parse_status(0xFF), None);
+
+        let hosts = subnet_hosts(Ipv4Addr::new(192, 168, 1, 20));
+        assert_eq!(hosts.len(), 253);
+        assert_eq!(hosts[0], Ipv4Addr::new(192, 168, 1, 1));
+        assert!(!hosts.contains(&Ipv4Addr::new(192, 168, 1, 20)));
+
+        let q = mdns_query("_pdl-datastream._tcp.local");
+        assert_eq!(&q[4..6], &[0, 1]);
+        assert_eq!(q[12], 15);
+        assert_eq!(&q[13..28], b"_pdl-datastream");
+        assert_eq!(&q[q.len() - 5..], &[0, 0, 12, 0x80, 1]);
+    }
+}
@@ -0,0 +1,295 @@
+//! Puter cloud storage for backups, attachments and shared documents, using the app_id/auth_token kept in the secure
+//! store. Files live under ~/AppData/<app_id>/shafaf/{backups,attachments,shares}; transfers emit
+//! "puter-sync-progress" events.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+const DEFAULT_API_ORIGIN: &str = "https://api.puter.com";
+pub const PROGRESS_EVENT: &str = "puter-sync-progress";
+/// Emit at most one progress event per this many bytes.
+const PROGRESS_STEP_BYTES: u64 = 256 * 1024;
+const REQUEST_TIMEOUT_SECS: u64 = 600;
+
+/// Remote folders the app syncs.
+pub const FOLDERS: [&str; 3] = ["backups", "attachments", "shares"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteFile {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    /// Unix seconds.
+    pub modified: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncProgress {
+    /// "upload" or "download".
+    pub operation: String,
+    pub name: String,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Signature {
+    read_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignResponse {
+    #[serde(default)]
+    signatures: Vec<Signature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReaddirEntry {
+    name: String,
+    path: String,
+    #[serde(default)]
+    is_dir: bool,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    modified: Option<f64>,
+}
+
+pub struct PuterClient {
+    origin: String,
+    app_id: String,
+    auth_token: String,
+    http: reqwest::blocking::Client,
+}
+
+impl PuterClient {
+    pub fn new(app_id: String, auth_token: String) -> Result<Self, String> {
+        let origin = std::env::var("PUTER_API_ORIGIN")
+            .ok()
+            .filter(|o| !o.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_API_ORIGIN.to_string());
+        let http = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| format!("HTTP client error: {}", e))?;
+        Ok(PuterClient {
+            origin: origin.trim_end_matches('/').to_string(),
+            app_id,
+            auth_token,
+            http,
+        })
+    }
+
+    fn folder_path(&self, folder: &str) -> Result<String, String> {
+        if !FOLDERS.contains(&folder) {
+            return Err(format!("Unknown Puter folder: {}", folder));
+        }
+        Ok(format!("~/AppData/{}/shafaf/{}", self.app_id, folder))
+    }
+
+    fn post_json(&self, endpoint: &str, body: serde_json::Value) -> Result<reqwest::blocking::Response, String> {
+        let response = self
+            .http
+            .post(format!("{}/{}", self.origin, endpoint))
+            .bearer_auth(&self.auth_token)
+            .json(&body)
+            .send()
+            .map_err(|e| format!("Puter request failed: {}", e))?;
+        check_status(response)
+    }
+
+    fn ensure_folder(&self, folder: &str) -> Result<String, String> {
+        let path = self.folder_path(folder)?;
+        // mkdir fails when the folder exists; that's fine as long as readdir works afterwards
+        let _ = self.post_json(
+            "mkdir",
+            serde_json::json!({ "path": path, "create_missing_parents": true, "overwrite": false }),
+        );
+        Ok(path)
+    }
+
+    /// List files in one of the synced folders, newest first.
+    pub fn list(&self, folder: &str) -> Result<Vec<RemoteFile>, String> {
+        let path = self.ensure_folder(folder)?;
+        let entries: Vec<ReaddirEntry> = self
+            .post_json("readdir", serde_json::json!({ "path": path }))?
+            .json()
+            .map_err(|e| format!("Invalid Puter response: {}", e))?;
+        let mut files: Vec<RemoteFile> = entries
+            .into_iter()
+            .filter(|e| !e.is_dir)
+            .map(|e| RemoteFile {
+                name: e.name,
+                path: e.path,
+                size: e.size.unwrap_or(0),
+                modified: e.modified,
+            })
+            .collect();
+        files.sort_by(|a, b| b.modified.partial_cmp(&a.modified).unwrap_or(std::cmp::Ordering::Equal));
+        Ok(files)
+    }
+
+    /// Upload a local file into a synced folder (overwrites a file with the same name). Returns the remote path.
+    pub fn upload(&self, app: &AppHandle, folder: &str, local_path: &Path) -> Result<String, String> {
+        let dir = self.ensure_folder(folder)?;
+        let name = local_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or("Invalid file path")?;
+        let file = fs::File::open(local_path).map_err(|e| format!("Failed to open {}: {}", local_path.display(), e))?;
+        let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+        let reader = ProgressReader::new(file, app.clone(), "upload", name.clone(), total);
+        let operation = serde_json::json!({
+            "op": "write",
+            "path": dir,
+            "name": name,
+            "overwrite": true,
+            "dedupe_name": false,
+            "create_missing_parents": true,
+        });
+        let fileinfo = serde_json::json!({ "name": name, "type": "application/octet-stream", "size": total });
+        let form = reqwest::blocking::multipart::Form::new()
+            .text("operation_id", new_operation_id())
+            .text("fileinfo", fileinfo.to_string())
+            .text("operation", operation.to_string())
+            .part(
+                "file",
+                reqwest::blocking::multipart::Part::reader_with_length(reader, total).file_name(name.clone()),
+            );
+        let response = self
+            .http
+            .post(format!("{}/batch", self.origin))
+            .bearer_auth(&self.auth_token)
+            .multipart(form)
+            .send()
+            .map_err(|e| format!("Puter upload failed: {}", e))?;
+        check_status(response)?;
+        emit_progress(app, "upload", &name, total, total);
+        Ok(format!("{}/{}", dir, name))
+    }
+
+    /// Signed URL anyone can open to read a remote file, without signing in to Puter.
+    pub fn public_url(&self, remote_path: &str) -> Result<String, String> {
+        let response: SignResponse = self
+            .post_json("sign", serde_json::json!({ "items": [{ "path": remote_path, "action": "read" }] }))?
+            .json()
+            .map_err(|e| format!("Invalid Puter response: {}", e))?;
+        response
+            .signatures
+            .into_iter()
+            .next()
+            .and_then(|s| s.read_url)
+            .ok_or_else(|| "Puter did not return a link for the file".to_string())
+    }
+
+    /// Delete a remote file; its signed URLs stop working.
+    pub fn delete(&self, remote_path: &str) -> Result<(), String> {
+        self.post_json("delete", serde_json::json!({ "paths": [remote_path] }))?;
+        Ok(())
+    }
+
+    /// Download a remote file to dest_path (written to a temp file first, then renamed).
+    pub fn download(&self, app: &AppHandle, remote_path: &str, dest_path: &Path) -> Result<(), String> {
+        let mut response = check_status(
+            self.http
+                .get(format!("{}/read", self.origin))
+                .query(&[("file", remote_path)])
+                .bearer_auth(&self.auth_token)
+                .send()
+                .map_err(|e| format!("Puter download failed: {}", e))?,
+        )?;
+        let name = remote_path.rsplit('/').next().unwrap_or(remote_path).to_string();
+        let total = response.content_length().unwrap_or(0);
+        if let Some(dir) = dest_path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create folder: {}", e))?;
+        }
+        let tmp_path = dest_path.with_extension("part");
+        let mut out = fs::File::create(&tmp_path).map_err(|e| format!("Failed to create {}: {}", tmp_path.display(), e))?;
+
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut done: u64 = 0;
+        let mut last_emit: u64 = 0;
+        loop {
+            let n = response.read(&mut buf).map_err(|e| format!("Puter download failed: {}", e))?;
+            if n == 0 {
+                break;
+            }
+            out.write_all(&buf[..n]).map_err(|e| format!("Failed to write file: {}", e))?;
+            done += n as u64;
+            if done - last_emit >= PROGRESS_STEP_BYTES {
+                emit_progress(app, "download", &name, done, total);
+                last_emit = done;
+            }
+        }
+        out.flush().map_err(|e| format!("Failed to write file: {}", e))?;
+        drop(out);
+        fs::rename(&tmp_path, dest_path).map_err(|e| format!("Failed to save {}: {}", dest_path.display(), e))?;
+        emit_progress(app, "download", &name, done, total.max(done));
+        Ok(())
+    }
+}
+
+fn check_status(response: reqwest::blocking::Response) -> Result<reqwest::blocking::Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().unwrap_or_default();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err("Puter rejected the stored credentials; update them in settings".to_string());
+    }
+    Err(format!("Puter error {}: {}", status, body.chars().take(200).collect::<String>()))
+}
+
+fn new_operation_id() -> String {
+    use aes_gcm::aead::{rand_core::RngCore, OsRng};
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn emit_progress(app: &AppHandle, operation: &str, name: &str, bytes_done: u64, bytes_total: u64) {
+    let _ = app.emit(
+        PROGRESS_EVENT,
+        SyncProgress {
+            operation: operation.to_string(),
+            name: name.to_string(),
+            bytes_done,
+            bytes_total,
+        },
+    );
+}
+
+/// Wraps the upload body so progress is reported as reqwest reads it.
+struct ProgressReader<R> {
+    inner: R,
+    app: AppHandle,
+    operation: &'static str,
+    name: String,
+    total: u64,
+    done: u64,
+    last_emit: u64,
+}
+
+impl<R: Read> ProgressReader<R> {
+    fn new(inner: R, app: AppHandle, operation: &'static str, name: String, total: u64) -> Self {
+        ProgressReader { inner, app, operation, name, total, done: 0, last_emit: 0 }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.done += n as u64;
+        if self.done - self.last_emit >= PROGRESS_STEP_BYTES {
+            emit_progress(&self.app, self.operation, &self.name, self.done, self.total);
+            self.last_emit = self.done;
+        }
+        Ok(n)
+    }
+}
@@ -0,0 +1,77 @@
+//! Read-only mode for an expired license: the data stays available for lookups and reports while every command
+//! that could change it is refused.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Error returned to commands refused in read-only mode; the "license_expired" prefix lets the UI recognize it.
+pub const LICENSE_EXPIRED_ERROR: &str = "license_expired: لایسنس منقضی شده است، برنامه فقط قابل مشاهده است (License expired: the app is read-only)";
+
+/// Commands that only read, by name prefix.
+const READ_PREFIXES: [&str; 12] = [
+    "get_", "check_", "preview_", "validate_", "verify_", "find_", "search_", "list_", "trace_", "export_", "format_",
+    "print_",
+];
+
+/// Commands that stay available in read-only mode although their names do not say so: opening and closing the
+/// database, signing in, schema setup on open, backups, and renewing the license. db_query only takes read-only
+/// statements and runs them in a read-only transaction while this mode is on.
+const ALLOWED: [&str; 24] = [
+    "db_open",
+    "db_close",
+    "db_is_open",
+    "db_pool_status",
+    "db_query",
+    "save_env_config",
+    "login_user",
+    "logout_user",
+    "hash_password",
+    "store_license_key",
+    "register_license_on_server",
+    "refresh_license_expiry_from_server",
+    "backup_database",
+    "run_progressive_backup",
+    "puter_upload_backup",
+    "puter_list_files",
+    "puter_download_file",
+    "sql_console_explain",
+    "sql_console_export_csv",
+    "discover_printers",
+    "adjust_due_date",
+    "generate_payslips",
+    "queue_print_job",
+    "retry_print_job",
+];
+
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+pub fn set_active(active: bool) {
+    ACTIVE.store(active, Ordering::Relaxed);
+}
+
+/// Whether a command may run in read-only mode. Table setup ("init_") only creates missing tables and columns.
+pub fn allows(command: &str) -> bool {
+    command.starts_with("init_") || READ_PREFIXES.iter().any(|p| command.starts_with(p)) || ALLOWED.contains(&command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows() {
+        assert!(allows("get_sales"));
+        assert!(allows("init_sales_table"));
+        assert!(allows("db_open"));
+        assert!(allows("store_license_key"));
+        assert!(!allows("create_sale"));
+        assert!(!allows("db_execute"));
+        assert!(!allows("sql_console_run"));
+        assert!(!allows("restore_database"));
+        assert!(!allows("approve_payroll_run"));
+        assert!(!allows("suggest_prices"));
+    }
+}
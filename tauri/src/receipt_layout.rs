@@ -0,0 +1,141 @@
+//! 80mm thermal receipt layout: 48 columns of the printer's standard font, with item rows and totals laid out in
+//! columns. Layout text marks a line's style with leading tags that the ESC/POS writer turns into printer commands:
+//! [C] centered, [R] right aligned, [B] bold and [H] double width and height (half the columns). Thermal templates
+//! may use the same tags.
+
+/// Columns of an 80mm receipt in the standard font (Font A).
+pub const WIDTH: usize = 48;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Style {
+    pub center: bool,
+    pub right: bool,
+    pub bold: bool,
+    pub large: bool,
+}
+
+impl Style {
+    /// Columns available on a line of this style.
+    pub fn width(&self) -> usize {
+        if self.large {
+            WIDTH / 2
+        } else {
+            WIDTH
+        }
+    }
+}
+
+/// The style tags at the start of a layout line and the text after them.
+pub fn parse_line(line: &str) -> (Style, &str) {
+    let mut style = Style::default();
+    let mut rest = line;
+    loop {
+        match rest.get(..3) {
+            Some("[C]") => style.center = true,
+            Some("[R]") => style.right = true,
+            Some("[B]") => style.bold = true,
+            Some("[H]") => style.large = true,
+            _ => return (style, rest),
+        }
+        rest = &rest[3..];
+    }
+}
+
+/// `text` cut to `width` characters, ending in "…" when cut. Leading spaces are kept for indented rows.
+pub fn fit(text: &str, width: usize) -> String {
+    let text = text.trim_end();
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        format!("{}…", text.chars().take(width.saturating_sub(1)).collect::<String>())
+    }
+}
+
+/// `left` and `right` on one line of `width` columns, `right` flush with the edge; `left` is cut to make room.
+pub fn columns(left: &str, right: &str, width: usize) -> String {
+    let right = fit(right, width);
+    let room = width.saturating_sub(right.chars().count() + 1);
+    let left = fit(left, room);
+    let gap = width - left.chars().count() - right.chars().count();
+    format!("{}{}{}", left, " ".repeat(gap), right)
+}
+
+pub struct ReceiptLine {
+    pub name: String,
+    /// Quantity and unit, e.g. "2 kg".
+    pub quantity: String,
+    pub price: String,
+    pub total: String,
+}
+
+/// A sale as printed. Amounts and dates come formatted; `totals` (subtotal, discount, ...), `total` and `payments`
+/// are (label, amount) rows.
+pub struct Receipt {
+    pub company: Vec<String>,
+    pub title: String,
+    pub details: Vec<String>,
+    pub lines: Vec<ReceiptLine>,
+    pub totals: Vec<(String, String)>,
+    pub total: (String, String),
+    pub payments: Vec<(String, String)>,
+    pub footer: Vec<String>,
+}
+
+/// Layout text of a receipt: company header in large type, the title and details, one row per item with its
+/// quantity, price and total under the name, then the totals with the grand total in bold, payments and the footer.
+pub fn render(receipt: &Receipt) -> String {
+    let rule = "-".repeat(WIDTH);
+    let mut out: Vec<String> = Vec::new();
+    for (i, line) in receipt.company.iter().filter(|l| !l.trim().is_empty()).enumerate() {
+        out.push(if i == 0 { format!("[C][B][H]{}", fit(line, WIDTH / 2)) } else { format!("[C]{}", fit(line, WIDTH)) });
+    }
+    out.push(format!("[C][B]{}", fit(&receipt.title, WIDTH)));
+    out.extend(receipt.details.iter().filter(|d| !d.trim().is_empty()).map(|d| fit(d, WIDTH)));
+    out.push(rule.clone());
+    for line in &receipt.lines {
+        out.push(fit(&line.name, WIDTH));
+        out.push(columns(&format!("  {} x {}", line.quantity, line.price), &line.total, WIDTH));
+    }
+    out.push(rule);
+    out.extend(receipt.totals.iter().map(|(label, amount)| columns(label, amount, WIDTH)));
+    out.push(format!("[B]{}", columns(&receipt.total.0, &receipt.total.1, WIDTH)));
+    out.extend(receipt.payments.iter().map(|(label, amount)| columns(label, amount, WIDTH)));
+    out.push(String::new());
+    out.extend(receipt.footer.iter().map(|f| format!("[C]{}", fit(f, WIDTH))));
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_receipt_layout() {
+        assert_eq!(columns("Total", "1,250.00 ؋", 20), "Total     1,250.00 ؋");
+        assert_eq!(columns("Basmati rice premium", "90.00", 16), "Basmati r… 90.00");
+        assert_eq!(parse_line("[C][B]Shafaf"), (Style { center: true, bold: true, ..Style::default() }, "Shafaf"));
+        assert_eq!(parse_line("[X] text"), (Style::default(), "[X] text"));
+        assert_eq!(parse_line("[H]Big").0.width(), 24);
+
+        let receipt = Receipt {
+            company: vec!["Shafaf Store".to_string(), "Kabul".to_string()],
+            title: "Invoice INV-2024-000042".to_string(),
+            details: vec!["1 February 2024".to_string(), String::new()],
+            lines: vec![ReceiptLine { name: "Tea 500g".to_string(), quantity: "2".to_string(), price: "125.00".to_string(), total: "250.00".to_string() }],
+            totals: vec![("Subtotal".to_string(), "250.00".to_string())],
+            total: ("Total".to_string(), "250.00 ؋".to_string()),
+            payments: vec![("Paid".to_string(), "250.00 ؋".to_string())],
+            footer: vec!["Thank you".to_string()],
+        };
+        let text = render(&receipt);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "[C][B][H]Shafaf Store");
+        assert_eq!(lines[2], "[C][B]Invoice INV-2024-000042");
+        assert_eq!(lines[3], "1 February 2024");
+        assert_eq!(lines[6].chars().count(), WIDTH);
+        assert!(lines[6].starts_with("  2 x 125.00") && lines[6].ends_with("250.00"));
+        assert!(lines[9].starts_with("[B]Total") && lines[9].ends_with("250.00 ؋"));
+        assert!(lines[10].starts_with("Paid"));
+        assert_eq!(lines.last(), Some(&"[C]Thank you"));
+    }
+}
@@ -0,0 +1,268 @@
+//! Reading a SQL dump for a restore: its statements and the table each one works on, so the users table can be
+//! skipped, replaced or merged, and the row counts the dump restores, so a restore can be checked afterwards.
+
+use std::collections::BTreeMap;
+
+/// How a restore treats the `users` table of the dump.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UsersRestore {
+    /// Leave the current users as they are.
+    Skip,
+    /// Replace the users table with the one in the dump.
+    Include,
+    /// Keep the current users and add the dump's users whose id and username are not taken.
+    Merge,
+}
+
+/// What a statement does to a table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Verb {
+    Drop,
+    Create,
+    Alter,
+    Lock,
+    Unlock,
+    /// An INSERT or REPLACE, with the length of the keywords before the table name.
+    Insert(usize),
+    Other,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Code,
+    Quoted(char),
+    LineComment,
+    BlockComment,
+}
+
+/// Statements of a dump, split on semicolons outside strings, identifiers and comments.
+fn statements(sql: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut state = State::Code;
+    let mut chars = sql.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|(_, n)| *n);
+        match state {
+            State::Code => match c {
+                '\'' | '"' | '`' => state = State::Quoted(c),
+                '-' if next == Some('-') => state = State::LineComment,
+                '#' => state = State::LineComment,
+                '/' if next == Some('*') => {
+                    chars.next();
+                    state = State::BlockComment;
+                }
+                ';' => {
+                    out.push(&sql[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            },
+            State::Quoted(q) => {
+                if c == '\\' && q != '`' {
+                    chars.next();
+                } else if c == q {
+                    state = State::Code;
+                }
+            }
+            State::LineComment => {
+                if c == '\n' {
+                    state = State::Code;
+                }
+            }
+            State::BlockComment => {
+                if c == '*' && next == Some('/') {
+                    chars.next();
+                    state = State::Code;
+                }
+            }
+        }
+    }
+    if !sql[start..].trim().is_empty() {
+        out.push(&sql[start..]);
+    }
+    out
+}
+
+/// The statement without the comment lines and blank lines before it.
+fn head(stmt: &str) -> &str {
+    let mut rest = stmt.trim_start();
+    while rest.starts_with("--") || rest.starts_with('#') {
+        rest = rest.split_once('\n').map(|(_, r)| r).unwrap_or("").trim_start();
+    }
+    rest
+}
+
+/// The table name at the start of `rest`: `quoted`, "quoted" or bare.
+fn table_name(rest: &str) -> Option<String> {
+    let rest = rest.trim_start();
+    let name = match rest.chars().next() {
+        Some(q @ ('`' | '"')) => rest[1..].split(q).next()?,
+        _ => rest.split(|c: char| c.is_whitespace() || c == '(' || c == ',').next()?,
+    };
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// What `stmt` (comment lines before it skipped) does and to which table. Version comments such as
+/// "/*!40000 ALTER TABLE `t` DISABLE KEYS */" count as the statement inside them.
+fn target(stmt: &str) -> (Verb, Option<String>) {
+    let mut body = stmt;
+    if let Some(inner) = body.strip_prefix("/*!") {
+        body = inner.trim_start_matches(|c: char| c.is_ascii_digit()).trim_end().trim_end_matches("*/").trim();
+    }
+    let upper = body.get(..40).unwrap_or(body).to_ascii_uppercase();
+    let keywords: [(&str, Verb); 10] = [
+        ("DROP TABLE IF EXISTS", Verb::Drop),
+        ("DROP TABLE", Verb::Drop),
+        ("CREATE TABLE IF NOT EXISTS", Verb::Create),
+        ("CREATE TABLE", Verb::Create),
+        ("ALTER TABLE", Verb::Alter),
+        ("LOCK TABLES", Verb::Lock),
+        ("UNLOCK TABLES", Verb::Unlock),
+        ("INSERT IGNORE INTO", Verb::Insert("INSERT IGNORE INTO".len())),
+        ("INSERT INTO", Verb::Insert("INSERT INTO".len())),
+        ("REPLACE INTO", Verb::Insert("REPLACE INTO".len())),
+    ];
+    let found = keywords.into_iter().find(|(k, _)| {
+            let rest = upper.strip_prefix(k);
+            rest.is_some_and(|r| r.is_empty() || r.starts_with(|c: char| c.is_whitespace() || c == '`' || c == '"'))
+        });
+    match found {
+        Some((_, Verb::Unlock)) => (Verb::Unlock, None),
+        Some((k, verb)) => (verb, table_name(&body[k.len()..])),
+        None => (Verb::Other, None),
+    }
+}
+
+/// The statements of `sql` to run for a restore, each ending in ";", with the `users` table handled per `users`.
+/// When merging, the users table keeps its definition and the dump's rows go in with INSERT IGNORE.
+pub fn restore_script(sql: &str, users: UsersRestore) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut locked_users = false;
+    for stmt in statements(sql) {
+        let start = stmt.len() - head(stmt).len();
+        let (verb, table) = target(head(stmt));
+        let on_users = match verb {
+            Verb::Unlock => std::mem::take(&mut locked_users),
+            _ => table.as_deref() == Some("users"),
+        };
+        if verb == Verb::Lock {
+            locked_users = on_users;
+        }
+        let keep = match (on_users, users, verb) {
+            (false, _, _) | (true, UsersRestore::Include, _) => Some(stmt.to_string()),
+            (true, UsersRestore::Merge, Verb::Insert(len)) => {
+                Some(format!("{}INSERT IGNORE INTO{}", &stmt[..start], &stmt[start + len..]))
+            }
+            (true, UsersRestore::Merge, Verb::Lock | Verb::Unlock | Verb::Alter) => Some(stmt.to_string()),
+            (true, _, _) => None,
+        };
+        if let Some(keep) = keep {
+            out.push_str(&keep);
+            out.push(';');
+        }
+    }
+    out.push('\n');
+    out
+}
+
+/// Rows of an INSERT statement: the parenthesised tuples after VALUES.
+fn tuples(stmt: &str) -> u64 {
+    let Some(values) = stmt.to_ascii_uppercase().find("VALUES") else { return 0 };
+    let (mut depth, mut count, mut quote, mut escaped) = (0i32, 0u64, None::<char>, false);
+    for c in stmt[values + 6..].chars() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '\'' | '"' => quote = Some(c),
+                '(' => {
+                    if depth == 0 {
+                        count += 1;
+                    }
+                    depth += 1;
+                }
+                ')' => depth -= 1,
+                _ => {}
+            },
+        }
+    }
+    count
+}
+
+/// Rows per table the dump leaves behind: tables it creates start at 0 and each INSERT adds its rows.
+pub fn expected_row_counts(sql: &str) -> BTreeMap<String, u64> {
+    let mut counts = BTreeMap::new();
+    for stmt in statements(sql) {
+        let stmt = head(stmt);
+        match target(stmt) {
+            (Verb::Create, Some(table)) => {
+                counts.entry(table).or_insert(0);
+            }
+            (Verb::Insert(_), Some(table)) => *counts.entry(table).or_insert(0) += tuples(stmt),
+            _ => {}
+        }
+    }
+    counts
+}
+
+/// Tables whose row count differs from the dump, as "table: expected N rows, found M"; a table missing from
+/// `actual` is reported as missing.
+pub fn mismatches(expected: &BTreeMap<String, u64>, actual: &BTreeMap<String, u64>) -> Vec<String> {
+    expected
+        .iter()
+        .filter_map(|(table, rows)| match actual.get(table) {
+            None => Some(format!("{}: table missing after restore", table)),
+            Some(found) if found != rows => Some(format!("{}: expected {} rows, found {}", table, rows, found)),
+            Some(_) => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_row_counts() {
+        let dump = "-- MySQL dump 10.13\n/*!40101 SET NAMES utf8mb4 */;\n\
+            --\n-- Table structure for table `sales`\n--\n\n\
+            DROP TABLE IF EXISTS `sales`;\n\
+            CREATE TABLE `sales` (\n  `id` bigint NOT NULL,\n  `notes` text\n);\n\
+            CREATE TABLE IF NOT EXISTS `expenses` (`id` bigint);\n\
+            LOCK TABLES `sales` WRITE;\n\
+            INSERT INTO `sales` VALUES (1,'a;b'),(2,'it\\'s (here)'),(3,NULL);\n\
+            INSERT INTO `sales` (`id`, `notes`) VALUES (4,'-- not a comment');\n\
+            UNLOCK TABLES;\n-- Dump completed on 2024-02-01\n";
+        let expected = expected_row_counts(dump);
+        assert_eq!(expected.get("sales"), Some(&4));
+        assert_eq!(expected.get("expenses"), Some(&0));
+        assert_eq!(expected.len(), 2);
+
+        let actual: BTreeMap<String, u64> = [("sales".to_string(), 3)].into_iter().collect();
+        assert_eq!(
+            mismatches(&expected, &actual),
+            vec!["expenses: table missing after restore".to_string(), "sales: expected 4 rows, found 3".to_string()]
+        );
+
+        let users = "DROP TABLE IF EXISTS `users`;\nCREATE TABLE `users` (`id` bigint);\nLOCK TABLES `users` WRITE;\n\
+            /*!40000 ALTER TABLE `users` DISABLE KEYS */;\nINSERT INTO `users` VALUES (1,'admin;x');\nUNLOCK TABLES;\n\
+            LOCK TABLES `units` WRITE;\nINSERT INTO `units` VALUES (1,'kg');\nUNLOCK TABLES;\n";
+        let skipped = restore_script(users, UsersRestore::Skip);
+        assert!(!skipped.contains("users"));
+        assert!(skipped.contains("LOCK TABLES `units` WRITE;\nINSERT INTO `units` VALUES (1,'kg');\nUNLOCK TABLES;"));
+        assert_eq!(skipped.matches("UNLOCK TABLES").count(), 1);
+        let merged = restore_script(users, UsersRestore::Merge);
+        assert!(!merged.contains("DROP TABLE") && !merged.contains("CREATE TABLE"));
+        assert!(merged.contains("INSERT IGNORE INTO `users` VALUES (1,'admin;x');"));
+        assert_eq!(merged.matches("UNLOCK TABLES").count(), 2);
+        assert_eq!(expected_row_counts(&restore_script(users, UsersRestore::Include)).get("users"), Some(&1));
+    }
+}
@@ -0,0 +1,58 @@
+//! Recency / frequency / monetary scoring of customers for targeted promotions.
+//! Each dimension is scored 1–5 by quintile across all customers (5 = most recent / most frequent / highest spend).
+
+/// Quintile score (1–5) for each value; ties get the same score. With `higher_is_better = false` small values score high.
+pub fn quintile_scores(values: &[f64], higher_is_better: bool) -> Vec<u8> {
+    let n = values.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut sorted: Vec<f64> = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    values
+        .iter()
+        .map(|v| {
+            // Number of values strictly below v, so equal values share a rank
+            let rank = sorted.partition_point(|x| x < v);
+            let score = (rank * 5 / n) as u8 + 1;
+            if higher_is_better {
+                score
+            } else {
+                6 - score
+            }
+        })
+        .collect()
+}
+
+/// Segment name for an R/F/M score triple.
+pub fn segment(recency: u8, frequency: u8, monetary: u8) -> &'static str {
+    match (recency, frequency, monetary) {
+        (4..=5, 4..=5, 4..=5) => "champions",
+        (_, 4..=5, _) if recency >= 3 => "loyal",
+        (5, 1, _) => "new",
+        (4..=5, 2..=3, _) => "potential_loyalist",
+        (1..=2, 3..=5, _) => "at_risk",
+        (1, 1, _) => "lost",
+        (1..=2, _, _) => "hibernating",
+        _ => "need_attention",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quintiles_and_segments() {
+        assert_eq!(quintile_scores(&[10.0, 20.0, 30.0, 40.0, 50.0], true), vec![1, 2, 3, 4, 5]);
+        assert_eq!(quintile_scores(&[1.0, 400.0, 1.0], false), vec![5, 2, 5]);
+        assert!(quintile_scores(&[], true).is_empty());
+
+        assert_eq!(segment(5, 5, 5), "champions");
+        assert_eq!(segment(3, 4, 2), "loyal");
+        assert_eq!(segment(5, 1, 1), "new");
+        assert_eq!(segment(1, 4, 4), "at_risk");
+        assert_eq!(segment(1, 1, 1), "lost");
+        assert_eq!(segment(3, 2, 3), "need_attention");
+    }
+}
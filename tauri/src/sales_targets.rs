@@ -0,0 +1,85 @@
+//! Sales targets: periods are a month ("2024-03") or a quarter ("2024-Q1"); dates are "YYYY-MM-DD" strings.
+
+/// salesperson: sales created by one user; branch: all sales of this installation; category: sale lines of one
+/// product category.
+pub const TARGET_SCOPES: [&str; 3] = ["salesperson", "branch", "category"];
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// First and last month of a period, None when it is neither a month nor a quarter.
+fn months(period: &str) -> Option<(i32, u32, u32)> {
+    let (year, rest) = period.split_once('-')?;
+    let year: i32 = year.parse().ok().filter(|y| (1900..=9999).contains(y))?;
+    if let Some(quarter) = rest.strip_prefix('Q') {
+        let q: u32 = quarter.parse().ok().filter(|q| (1..=4).contains(q))?;
+        return Some((year, q * 3 - 2, q * 3));
+    }
+    let month: u32 = rest.parse().ok().filter(|m| (1..=12).contains(m))?;
+    (rest.len() == 2).then_some((year, month, month))
+}
+
+/// First and last day of a period.
+pub fn period_range(period: &str) -> Option<(String, String)> {
+    let (year, first, last) = months(period)?;
+    Some((format!("{:04}-{:02}-01", year, first), format!("{:04}-{:02}-{:02}", year, last, days_in_month(year, last))))
+}
+
+/// The month and the quarter a date falls in.
+pub fn periods_of(date: &str) -> Option<[String; 2]> {
+    let year: i32 = date.get(0..4)?.parse().ok()?;
+    let month: u32 = date.get(5..7)?.parse().ok().filter(|m| (1..=12).contains(m))?;
+    Some([format!("{:04}-{:02}", year, month), format!("{:04}-Q{}", year, (month + 2) / 3)])
+}
+
+/// Actual as a percentage of the target, to one decimal.
+pub fn achievement_percent(actual: f64, target: f64) -> f64 {
+    if target <= 0.0 {
+        return 0.0;
+    }
+    (actual / target * 1000.0).round() / 10.0
+}
+
+/// Percentage of the period gone by the end of `today` (0 before it starts, 100 after it ends): the achievement
+/// a salesperson on pace would have now.
+pub fn elapsed_percent(period: &str, today: &str) -> f64 {
+    let Some((start, end)) = period_range(period) else { return 0.0 };
+    if today < start.as_str() {
+        return 0.0;
+    }
+    if today >= end.as_str() {
+        return 100.0;
+    }
+    let (year, first, last) = months(period).unwrap_or_default();
+    let total: u32 = (first..=last).map(|m| days_in_month(year, m)).sum();
+    let month: u32 = today.get(5..7).and_then(|m| m.parse().ok()).unwrap_or(first);
+    let day: u32 = today.get(8..10).and_then(|d| d.parse().ok()).unwrap_or(1);
+    let elapsed: u32 = (first..month).map(|m| days_in_month(year, m)).sum::<u32>() + day;
+    (elapsed as f64 / total as f64 * 1000.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_periods() {
+        assert_eq!(period_range("2024-02"), Some(("2024-02-01".to_string(), "2024-02-29".to_string())));
+        assert_eq!(period_range("2023-Q4"), Some(("2023-10-01".to_string(), "2023-12-31".to_string())));
+        assert_eq!(period_range("2024-13"), None);
+        assert_eq!(period_range("2024-Q5"), None);
+        assert_eq!(period_range("2024-3"), None);
+        assert_eq!(periods_of("2024-05-17"), Some(["2024-05".to_string(), "2024-Q2".to_string()]));
+        assert_eq!(achievement_percent(750.0, 1000.0), 75.0);
+        assert_eq!(achievement_percent(10.0, 0.0), 0.0);
+        assert_eq!(elapsed_percent("2024-04", "2024-04-15"), 50.0);
+        assert_eq!(elapsed_percent("2024-Q1", "2023-12-31"), 0.0);
+        assert_eq!(elapsed_percent("2024-Q1", "2024-04-02"), 100.0);
+    }
+}
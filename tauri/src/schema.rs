@@ -0,0 +1,91 @@
+//! Reading the embedded schema: the statements of db.sql and the column definitions of its CREATE TABLE statements,
+//! so an existing database can be brought up to date by adding what is missing.
+
+/// Statements of a SQL script without comment lines.
+pub fn statements(sql: &str) -> Vec<String> {
+    sql.split(';')
+        .map(|stmt| {
+            stmt.lines()
+                .filter(|line| !line.trim().starts_with("--"))
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim()
+                .to_string()
+        })
+        .filter(|stmt| !stmt.is_empty())
+        .collect()
+}
+
+/// Split on commas outside parentheses.
+fn split_top_level(body: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0i32, 0);
+    for (i, c) in body.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&body[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&body[start..]);
+    parts
+}
+
+/// Table name and (column, definition) pairs of a CREATE TABLE statement; None for other statements. Keys and
+/// constraints are left out.
+pub fn table_columns(stmt: &str) -> Option<(String, Vec<(String, String)>)> {
+    let rest = stmt.trim().strip_prefix("CREATE TABLE")?.trim_start();
+    let rest = rest.strip_prefix("IF NOT EXISTS").unwrap_or(rest).trim_start();
+    let open = rest.find('(')?;
+    let close = rest.rfind(')')?;
+    let table = rest[..open].trim().trim_matches('`').to_string();
+    let columns = split_top_level(&rest[open + 1..close])
+        .into_iter()
+        .filter_map(|def| {
+            let def = def.split_whitespace().collect::<Vec<_>>().join(" ");
+            let (name, definition) = def.split_once(' ')?;
+            let keyword = name.to_uppercase();
+            if ["PRIMARY", "INDEX", "KEY", "UNIQUE", "FOREIGN", "CONSTRAINT", "CHECK", "FULLTEXT"].contains(&keyword.as_str())
+                || keyword.starts_with("UNIQUE(")
+            {
+                return None;
+            }
+            Some((name.trim_matches('`').to_string(), definition.to_string()))
+        })
+        .collect();
+    Some((table, columns))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_columns() {
+        let sql = "-- schema\nCREATE TABLE IF NOT EXISTS account_currency_balances (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    rate DECIMAL(18, 6) NOT NULL DEFAULT 1,
+    note VARCHAR(32),
+    INDEX idx_note (note),
+    FOREIGN KEY (id) REFERENCES accounts(id) ON DELETE CASCADE,
+    UNIQUE(id, rate)
+);\n\nINSERT IGNORE INTO units (name) VALUES ('a');\n";
+        let stmts = statements(sql);
+        assert_eq!(stmts.len(), 2);
+        let (table, columns) = table_columns(&stmts[0]).unwrap();
+        assert_eq!(table, "account_currency_balances");
+        assert_eq!(
+            columns,
+            vec![
+                ("id".to_string(), "BIGINT PRIMARY KEY AUTO_INCREMENT".to_string()),
+                ("rate".to_string(), "DECIMAL(18, 6) NOT NULL DEFAULT 1".to_string()),
+                ("note".to_string(), "VARCHAR(32)".to_string()),
+            ]
+        );
+        assert!(table_columns(&stmts[1]).is_none());
+    }
+}
@@ -0,0 +1,305 @@
+//! Secret storage for license data, Puter credentials and local keys.
+//! Uses the OS keyring; when it is unavailable (e.g. Linux without a secret service) values go to an
+//! AES-GCM encrypted file in the config dir, keyed from the machine ID. SECURE_STORE_BACKEND=keyring|file forces one.
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+const SERVICE: &str = "finance_app";
+const STORE_FILE: &str = "secure_store.enc";
+const FILE_KEY_SALT: &str = "finance-app-secure-store-2024";
+
+/// Every entry the app stores; migration between backends moves these.
+pub const KNOWN_ENTRIES: [&str; 7] = [
+    "mysql_password",
+    "license_key",
+    "license_expiry",
+    "license_last_seen",
+    "field_encryption_key",
+    "puter_app_id",
+    "puter_auth_token",
+];
+
+/// Serializes read-modify-write of the store file.
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+static FILE_KEY: OnceLock<[u8; 32]> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    Keyring,
+    File,
+}
+
+impl Backend {
+    pub fn parse(s: &str) -> Result<Backend, String> {
+        match s.trim().to_lowercase().as_str() {
+            "keyring" => Ok(Backend::Keyring),
+            "file" => Ok(Backend::File),
+            other => Err(format!("Unknown secure store backend: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecureStoreStatus {
+    /// Backend new values are written to.
+    pub active: Backend,
+    pub keyring_available: bool,
+    /// Set when SECURE_STORE_BACKEND pins the backend.
+    pub forced: Option<Backend>,
+    pub file_path: String,
+    /// Known entry names present in each backend.
+    pub keyring_entries: Vec<String>,
+    pub file_entries: Vec<String>,
+}
+
+fn forced_backend() -> Option<Backend> {
+    std::env::var("SECURE_STORE_BACKEND").ok().and_then(|v| Backend::parse(&v).ok())
+}
+
+// ---------- keyring ----------
+
+/// Ok(None) = no entry; Err = keyring unusable.
+fn keyring_get(name: &str) -> Result<Option<String>, keyring::Error> {
+    match keyring::Entry::new(SERVICE, name)?.get_password() {
+        Ok(v) => Ok(Some(v)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn keyring_set(name: &str, value: &str) -> Result<(), keyring::Error> {
+    keyring::Entry::new(SERVICE, name)?.set_password(value)
+}
+
+fn keyring_delete(name: &str) -> Result<(), keyring::Error> {
+    match keyring::Entry::new(SERVICE, name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Probe with a read: a missing entry still means the keyring works.
+pub fn keyring_available() -> bool {
+    keyring_get("license_key").is_ok()
+}
+
+// ---------- encrypted file ----------
+
+fn store_path() -> PathBuf {
+    crate::get_config_dir().join(STORE_FILE)
+}
+
+/// Prefer the OS machine ID (stable across kernel updates) and fall back to the hardware-based license machine ID.
+fn machine_id() -> String {
+    fs::read_to_string("/etc/machine-id")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(crate::license::generate_machine_id)
+}
+
+fn file_key() -> [u8; 32] {
+    *FILE_KEY.get_or_init(|| derive_file_key(&machine_id()))
+}
+
+fn derive_file_key(machine_id: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(FILE_KEY_SALT.as_bytes());
+    hasher.update(machine_id.as_bytes());
+    hasher.finalize().into()
+}
+
+/// hex(random nonce || ciphertext)
+fn seal(key: &[u8; 32], plaintext: &str) -> Result<String, String> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| format!("Failed to create cipher: {}", e))?;
+    let mut nonce_bytes = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), plaintext.as_bytes())
+        .map_err(|e| format!("Encryption error: {}", e))?;
+    let mut combined = nonce_bytes.to_vec();
+    combined.extend_from_slice(&ciphertext);
+    Ok(hex::encode(combined))
+}
+
+fn unseal(key: &[u8; 32], sealed: &str) -> Result<String, String> {
+    let bytes = hex::decode(sealed.trim()).map_err(|e| format!("Invalid hex: {}", e))?;
+    if bytes.len() < 12 {
+        return Err("Ciphertext too short".to_string());
+    }
+    let (nonce_bytes, ciphertext) = bytes.split_at(12);
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| format!("Failed to create cipher: {}", e))?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|_| "Secure store file cannot be decrypted on this machine".to_string())?;
+    String::from_utf8(plaintext).map_err(|e| format!("Invalid UTF-8: {}", e))
+}
+
+fn read_file_map() -> Result<BTreeMap<String, String>, String> {
+    let path = store_path();
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read secure store: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid secure store file: {}", e))
+}
+
+fn write_file_map(map: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = store_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(map).map_err(|e| e.to_string())?;
+    // Write then rename so a crash never leaves a truncated store
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content).map_err(|e| format!("Failed to write secure store: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600));
+    }
+    fs::rename(&tmp, &path).map_err(|e| format!("Failed to write secure store: {}", e))
+}
+
+fn file_get(name: &str) -> Result<Option<String>, String> {
+    let _lock = FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    match read_file_map()?.get(name) {
+        Some(sealed) => unseal(&file_key(), sealed).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn file_set(name: &str, value: &str) -> Result<(), String> {
+    let _lock = FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut map = read_file_map()?;
+    map.insert(name.to_string(), seal(&file_key(), value)?);
+    write_file_map(&map)
+}
+
+fn file_delete(name: &str) -> Result<(), String> {
+    let _lock = FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut map = read_file_map()?;
+    if map.remove(name).is_some() {
+        write_file_map(&map)?;
+    }
+    Ok(())
+}
+
+// ---------- public API ----------
+
+/// Read a secret. In automatic mode the keyring wins; the file is consulted when the keyring has no entry or fails.
+pub fn get(name: &str) -> Result<Option<String>, String> {
+    match forced_backend() {
+        Some(Backend::Keyring) => keyring_get(name).map_err(|e| format!("Failed to read {} from keyring: {}", name, e)),
+        Some(Backend::File) => file_get(name),
+        None => match keyring_get(name) {
+            Ok(Some(v)) => Ok(Some(v)),
+            Ok(None) | Err(_) => file_get(name),
+        },
+    }
+}
+
+/// Store a secret in the keyring, or in the encrypted file when the keyring is unavailable.
+pub fn set(name: &str, value: &str) -> Result<(), String> {
+    match forced_backend() {
+        Some(Backend::Keyring) => keyring_set(name, value).map_err(|e| format!("Failed to store {} in keyring: {}", name, e)),
+        Some(Backend::File) => file_set(name, value),
+        None => match keyring_set(name, value) {
+            // Drop any older copy from the fallback file so it can't shadow the keyring later
+            Ok(()) => file_delete(name),
+            Err(e) => {
+                eprintln!("⚠️ Keyring unavailable ({}); storing {} in encrypted file", e, name);
+                file_set(name, value)
+            }
+        },
+    }
+}
+
+/// Remove a secret from both backends.
+pub fn delete(name: &str) -> Result<(), String> {
+    let _ = keyring_delete(name);
+    file_delete(name)
+}
+
+pub fn status() -> SecureStoreStatus {
+    let keyring_available = keyring_available();
+    let forced = forced_backend();
+    let active = forced.unwrap_or(if keyring_available { Backend::Keyring } else { Backend::File });
+    let keyring_entries = if keyring_available {
+        KNOWN_ENTRIES
+            .iter()
+            .filter(|n| matches!(keyring_get(n), Ok(Some(_))))
+            .map(|n| n.to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let file_entries = {
+        let _lock = FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        read_file_map()
+            .map(|m| m.keys().filter(|k| KNOWN_ENTRIES.contains(&k.as_str())).cloned().collect())
+            .unwrap_or_default()
+    };
+    SecureStoreStatus {
+        active,
+        keyring_available,
+        forced,
+        file_path: store_path().to_string_lossy().to_string(),
+        keyring_entries,
+        file_entries,
+    }
+}
+
+/// Move every known entry into `target`, removing it from the other backend once copied. Returns the number moved.
+pub fn migrate(target: Backend) -> Result<usize, String> {
+    if target == Backend::Keyring && !keyring_available() {
+        return Err("OS keyring is not available on this system".to_string());
+    }
+    let mut moved = 0;
+    for name in KNOWN_ENTRIES {
+        let value = match target {
+            Backend::Keyring => file_get(name)?,
+            Backend::File => keyring_get(name).map_err(|e| format!("Failed to read {} from keyring: {}", name, e))?,
+        };
+        let value = match value {
+            Some(v) => v,
+            None => continue,
+        };
+        match target {
+            Backend::Keyring => {
+                keyring_set(name, &value).map_err(|e| format!("Failed to store {} in keyring: {}", name, e))?;
+                file_delete(name)?;
+            }
+            Backend::File => {
+                file_set(name, &value)?;
+                keyring_delete(name).map_err(|e| format!("Failed to remove {} from keyring: {}", name, e))?;
+            }
+        }
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_roundtrip_is_machine_bound() {
+        let key = derive_file_key("machine-a");
+        let sealed = seal(&key, "LICENSE-123").unwrap();
+        assert_ne!(sealed, seal(&key, "LICENSE-123").unwrap());
+        assert_eq!(unseal(&key, &sealed).unwrap(), "LICENSE-123");
+        assert!(unseal(&derive_file_key("machine-b"), &sealed).is_err());
+    }
+}
@@ -1,15 +1,18 @@
 use axum::{
     body::Body,
-    extract::State,
-    http::{Response, StatusCode},
+    extract::{Query, State},
+    http::{HeaderMap, Response, StatusCode},
     response::IntoResponse,
     routing::{get, post},
     Json,
     Router,
 };
+use crate::db::Database;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
 
 // Embed ai.html content at compile time for production
@@ -22,6 +25,43 @@ struct PuterCredentials {
     auth_token: String,
 }
 
+/// Fixed one-minute window request counter per API token id.
+#[derive(Default)]
+pub(crate) struct RateLimiter {
+    windows: Mutex<HashMap<i64, (Instant, i64)>>,
+}
+
+impl RateLimiter {
+    /// Count a request for token_id; returns Err(seconds until the window resets) when over limit.
+    fn check(&self, token_id: i64, limit: i64) -> Result<(), u64> {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let entry = windows.entry(token_id).or_insert((now, 0));
+        if now.duration_since(entry.0) >= Duration::from_secs(60) {
+            *entry = (now, 0);
+        }
+        if entry.1 >= limit {
+            let elapsed = now.duration_since(entry.0).as_secs();
+            return Err(60u64.saturating_sub(elapsed).max(1));
+        }
+        entry.1 += 1;
+        Ok(())
+    }
+}
+
+/// State for the token-authenticated REST API (/api/v1/*) and the optional GraphQL endpoint.
+#[derive(Clone)]
+pub(crate) struct ApiState {
+    pub(crate) app_handle: AppHandle,
+    pub(crate) limiter: Arc<RateLimiter>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListQuery {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
 /// Start the HTTP server on port 5021 to serve ai.html
 pub async fn start_server(app_handle: AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     // Try to find ai.html in multiple locations (for development)
@@ -87,6 +127,29 @@ pub async fn start_server(app_handle: AppHandle) -> Result<(), Box<dyn std::erro
         .route("/api/get-credentials", get(get_credentials))
         .with_state((ai_html_content.clone(), credentials_path));
 
+    // Read-only REST API for integrations; every request needs a Bearer API token with the matching scope
+    let api_state = ApiState {
+        app_handle: app_handle.clone(),
+        limiter: Arc::new(RateLimiter::default()),
+    };
+    let api = Router::new()
+        .route("/api/v1/products", get(api_products))
+        .route("/api/v1/customers", get(api_customers))
+        .route("/api/v1/suppliers", get(api_suppliers))
+        .route("/api/v1/sales", get(api_sales))
+        .route("/api/v1/purchases", get(api_purchases))
+        .route("/api/v1/stock", get(api_stock))
+        .with_state(api_state.clone());
+    let app = app.merge(api);
+    #[cfg(feature = "graphql")]
+    let app = app.merge(crate::graphql::router(api_state));
+    // Prometheus scrape endpoint, opt-in via METRICS_ENABLED in .env
+    let app = if crate::metrics::enabled() {
+        app.route("/metrics", get(serve_metrics))
+    } else {
+        app
+    };
+
     // Bind to all interfaces on port 5021
     let bind_addr = "0.0.0.0:5021";
     let listener = match tokio::net::TcpListener::bind(bind_addr).await {
@@ -125,6 +188,15 @@ async fn serve_ai_html(
         .unwrap()
 }
 
+/// Handler to serve metrics in Prometheus text format
+async fn serve_metrics() -> impl IntoResponse {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "text/plain; version=0.0.4")
+        .body(Body::from(crate::metrics::render()))
+        .unwrap()
+}
+
 /// Handler to store Puter credentials
 async fn store_credentials(
     State((_, credentials_path)): State<(String, Arc<PathBuf>)>,
@@ -209,3 +281,177 @@ async fn get_credentials(
         }
     }
 }
+
+// Read-only list queries shared by the REST API and GraphQL; each ends with LIMIT ? OFFSET ?.
+pub(crate) const PRODUCTS_SQL: &str = "SELECT id, name, description, price, currency_id, supplier_id, stock_quantity, unit, bar_code, created_at, updated_at FROM products ORDER BY id LIMIT ? OFFSET ?";
+
+pub(crate) const CUSTOMERS_SQL: &str = "SELECT id, full_name, phone, address, email, created_at, updated_at FROM customers ORDER BY id LIMIT ? OFFSET ?";
+
+pub(crate) const SUPPLIERS_SQL: &str = "SELECT id, full_name, phone, address, email, created_at, updated_at FROM suppliers ORDER BY id LIMIT ? OFFSET ?";
+
+pub(crate) const SALES_SQL: &str = "SELECT id, customer_id, date, currency_id, exchange_rate, total_amount, base_amount, paid_amount, created_at FROM sales ORDER BY id DESC LIMIT ? OFFSET ?";
+
+pub(crate) const PURCHASES_SQL: &str = "SELECT id, supplier_id, date, batch_number, currency_id, total_amount, created_at FROM purchases ORDER BY id DESC LIMIT ? OFFSET ?";
+
+/// Stock per product in base units, counting the same batch outflows as the desktop (sales and purchase returns, less
+/// sale returns).
+pub(crate) fn stock_sql() -> &'static str {
+    static SQL: OnceLock<String> = OnceLock::new();
+    SQL.get_or_init(|| {
+        format!(
+            "SELECT pr.id AS product_id, pr.name,
+        ROUND(COALESCE(SUM((pi.amount * COALESCE(u_pi.ratio, 1)) - COALESCE(sold.sold_base, 0)), 0), 6) AS stock_base
+    FROM products pr
+    LEFT JOIN purchase_items pi ON pi.product_id = pr.id
+    LEFT JOIN units u_pi ON u_pi.id = pi.unit_id
+    LEFT JOIN (
+        SELECT si.purchase_item_id, SUM(si.amount * COALESCE(u_si.ratio, 1)) AS sold_base
+        FROM {} si
+        LEFT JOIN units u_si ON u_si.id = si.unit_id
+        WHERE si.purchase_item_id IS NOT NULL
+        GROUP BY si.purchase_item_id
+    ) sold ON sold.purchase_item_id = pi.id
+    GROUP BY pr.id, pr.name
+    ORDER BY pr.id
+    LIMIT ? OFFSET ?",
+            crate::BATCH_OUTFLOWS_SQL
+        )
+    })
+}
+
+pub(crate) fn json_response(status: StatusCode, body: serde_json::Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .header("Access-Control-Allow-Origin", "*")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+pub(crate) fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+/// Verify the token has scope, count it against its rate limit, then run the read-only query (blocking; call from spawn_blocking).
+pub(crate) fn run_authorized_query(
+    state: &ApiState,
+    token: &str,
+    scope: &str,
+    sql: &str,
+    params: Vec<mysql::Value>,
+) -> Result<Vec<serde_json::Value>, (StatusCode, String)> {
+    let db_state = state.app_handle.state::<RwLock<Option<Database>>>();
+    let db_guard = db_state
+        .read()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Lock error: {}", e)))?;
+    let db = db_guard
+        .as_ref()
+        .ok_or((StatusCode::SERVICE_UNAVAILABLE, "No database is currently open".to_string()))?;
+    let api_token = crate::verify_api_token(db, token, scope).map_err(|e| (StatusCode::FORBIDDEN, e))?;
+    state
+        .limiter
+        .check(api_token.id, api_token.rate_limit_per_minute)
+        .map_err(|retry| (StatusCode::TOO_MANY_REQUESTS, format!("Rate limit exceeded, retry in {}s", retry)))?;
+    let mut rows = crate::query_json_objects(db, sql, params).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let cipher = crate::load_field_cipher(db);
+    for row in rows.iter_mut() {
+        crate::decrypt_json_field(&cipher, row, "phone");
+    }
+    Ok(rows)
+}
+
+/// Authenticate, rate-limit and run one read-only list query for the REST API.
+/// sql must end with "LIMIT ? OFFSET ?".
+async fn api_list(state: ApiState, headers: HeaderMap, query: ListQuery, scope: &'static str, sql: &'static str) -> Response<Body> {
+    let token = match bearer_token(&headers) {
+        Some(t) => t,
+        None => return json_response(StatusCode::UNAUTHORIZED, serde_json::json!({ "error": "Missing Bearer token" })),
+    };
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let result = tokio::task::spawn_blocking(move || {
+        run_authorized_query(&state, &token, scope, sql, vec![limit.into(), offset.into()])
+    })
+    .await;
+
+    match result {
+        Ok(Ok(rows)) => json_response(
+            StatusCode::OK,
+            serde_json::json!({ "items": rows, "limit": limit, "offset": offset }),
+        ),
+        Ok(Err((status, message))) => json_response(status, serde_json::json!({ "error": message })),
+        Err(e) => json_response(StatusCode::INTERNAL_SERVER_ERROR, serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+async fn api_products(State(state): State<ApiState>, headers: HeaderMap, Query(query): Query<ListQuery>) -> Response<Body> {
+    api_list(
+        state,
+        headers,
+        query,
+        "products:read",
+        PRODUCTS_SQL,
+    )
+    .await
+}
+
+async fn api_customers(State(state): State<ApiState>, headers: HeaderMap, Query(query): Query<ListQuery>) -> Response<Body> {
+    api_list(
+        state,
+        headers,
+        query,
+        "customers:read",
+        CUSTOMERS_SQL,
+    )
+    .await
+}
+
+async fn api_suppliers(State(state): State<ApiState>, headers: HeaderMap, Query(query): Query<ListQuery>) -> Response<Body> {
+    api_list(
+        state,
+        headers,
+        query,
+        "suppliers:read",
+        SUPPLIERS_SQL,
+    )
+    .await
+}
+
+async fn api_sales(State(state): State<ApiState>, headers: HeaderMap, Query(query): Query<ListQuery>) -> Response<Body> {
+    api_list(
+        state,
+        headers,
+        query,
+        "sales:read",
+        SALES_SQL,
+    )
+    .await
+}
+
+async fn api_purchases(State(state): State<ApiState>, headers: HeaderMap, Query(query): Query<ListQuery>) -> Response<Body> {
+    api_list(
+        state,
+        headers,
+        query,
+        "purchases:read",
+        PURCHASES_SQL,
+    )
+    .await
+}
+
+async fn api_stock(State(state): State<ApiState>, headers: HeaderMap, Query(query): Query<ListQuery>) -> Response<Body> {
+    api_list(
+        state,
+        headers,
+        query,
+        "stock:read",
+        stock_sql(),
+    )
+    .await
+}
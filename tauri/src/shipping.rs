@@ -0,0 +1,79 @@
+//! Delivery charges: rules priced flat, per kilogram or per liter, optionally limited to one delivery zone.
+
+/// flat: `rate` per delivery; per_kg: `rate` per kilogram of the order; per_liter: `rate` per liter of the order.
+pub const SHIPPING_METHODS: [&str; 3] = ["flat", "per_kg", "per_liter"];
+
+/// One active rule; amounts in base currency.
+pub struct Rule {
+    /// None applies to deliveries in zones without rules of their own.
+    pub zone: Option<String>,
+    pub method: String,
+    pub rate: f64,
+    pub min_charge: f64,
+    /// Orders totalling at least this are delivered free by the rule.
+    pub free_over: Option<f64>,
+}
+
+/// What is being delivered: total weight and volume of the order, and its total in base currency.
+pub struct Parcel {
+    pub weight_kg: f64,
+    pub volume_l: f64,
+    pub order_total: f64,
+}
+
+fn rule_charge(rule: &Rule, parcel: &Parcel) -> f64 {
+    if rule.free_over.is_some_and(|f| parcel.order_total >= f) {
+        return 0.0;
+    }
+    let charge = match rule.method.as_str() {
+        "per_kg" => rule.rate * parcel.weight_kg,
+        "per_liter" => rule.rate * parcel.volume_l,
+        _ => rule.rate,
+    };
+    (charge.max(rule.min_charge) * 100.0).round() / 100.0
+}
+
+/// Suggested delivery charge: the sum of the zone's rules (zone names compare case-insensitively), or of the
+/// zone-less rules when the zone has none. None when no rule applies.
+pub fn quote(rules: &[Rule], zone: Option<&str>, parcel: &Parcel) -> Option<f64> {
+    let zone = zone.map(str::trim).filter(|z| !z.is_empty());
+    let in_zone = |rule: &&Rule| match (&rule.zone, zone) {
+        (Some(r), Some(z)) => r.trim().eq_ignore_ascii_case(z),
+        _ => false,
+    };
+    let mut applicable: Vec<&Rule> = rules.iter().filter(in_zone).collect();
+    if applicable.is_empty() {
+        applicable = rules.iter().filter(|r| r.zone.is_none()).collect();
+    }
+    if applicable.is_empty() {
+        return None;
+    }
+    let total: f64 = applicable.iter().map(|r| rule_charge(r, parcel)).sum();
+    Some((total * 100.0).round() / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(zone: Option<&str>, method: &str, rate: f64, min_charge: f64, free_over: Option<f64>) -> Rule {
+        Rule { zone: zone.map(String::from), method: method.to_string(), rate, min_charge, free_over }
+    }
+
+    #[test]
+    fn test_quote() {
+        let rules = vec![
+            rule(None, "flat", 100.0, 0.0, Some(5000.0)),
+            rule(Some("Kabul"), "flat", 50.0, 0.0, None),
+            rule(Some("Kabul"), "per_kg", 10.0, 30.0, None),
+            rule(Some("Herat"), "per_liter", 2.5, 0.0, None),
+        ];
+        let parcel = Parcel { weight_kg: 12.0, volume_l: 40.0, order_total: 2000.0 };
+        assert_eq!(quote(&rules, Some("kabul"), &parcel), Some(170.0));
+        assert_eq!(quote(&rules, Some("Kabul"), &Parcel { weight_kg: 1.0, ..parcel }), Some(80.0));
+        assert_eq!(quote(&rules, Some("Herat"), &parcel), Some(100.0));
+        assert_eq!(quote(&rules, Some("Mazar"), &parcel), Some(100.0));
+        assert_eq!(quote(&rules, None, &Parcel { order_total: 6000.0, ..parcel }), Some(0.0));
+        assert_eq!(quote(&rules[1..], None, &parcel), None);
+    }
+}
@@ -0,0 +1,211 @@
+//! Statement analysis for the admin SQL console: read-only detection, automatic LIMIT injection and CSV output.
+//! Works on a light tokenization that skips string literals, quoted identifiers and comments.
+
+/// Statements the console treats as read-only.
+const READ_ONLY_KEYWORDS: [&str; 6] = ["SELECT", "SHOW", "DESCRIBE", "DESC", "EXPLAIN", "WITH"];
+/// Keywords that make a WITH/SELECT statement write (e.g. "WITH ... DELETE", "SELECT ... INTO OUTFILE", "FOR UPDATE" locks).
+const WRITE_KEYWORDS: [&str; 10] = [
+    "INSERT", "UPDATE", "DELETE", "REPLACE", "INTO", "CREATE", "DROP", "ALTER", "TRUNCATE", "LOCK",
+];
+
+/// Uppercased top-level words (depth = parenthesis nesting) with literals and comments removed.
+fn words(sql: &str) -> Vec<(String, usize)> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut out = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+    let mut word = String::new();
+    let flush = |word: &mut String, out: &mut Vec<(String, usize)>, depth: usize| {
+        if !word.is_empty() {
+            out.push((word.to_uppercase(), depth));
+            word.clear();
+        }
+    };
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' | '"' | '`' => {
+                flush(&mut word, &mut out, depth);
+                i += 1;
+                while i < chars.len() && chars[i] != c {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                flush(&mut word, &mut out, depth);
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '#' => {
+                flush(&mut word, &mut out, depth);
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                flush(&mut word, &mut out, depth);
+                i += 2;
+                while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                    i += 1;
+                }
+                i += 1;
+            }
+            '(' => {
+                flush(&mut word, &mut out, depth);
+                depth += 1;
+            }
+            ')' => {
+                flush(&mut word, &mut out, depth);
+                depth = depth.saturating_sub(1);
+            }
+            ';' => {
+                flush(&mut word, &mut out, depth);
+                out.push((";".to_string(), depth));
+            }
+            c if c.is_alphanumeric() || c == '_' => word.push(c),
+            _ => flush(&mut word, &mut out, depth),
+        }
+        i += 1;
+    }
+    flush(&mut word, &mut out, depth);
+    out
+}
+
+/// True when the text holds more than one statement (a trailing semicolon is fine).
+pub fn has_multiple_statements(sql: &str) -> bool {
+    let w = words(sql);
+    match w.iter().position(|(t, _)| t == ";") {
+        Some(pos) => w[pos + 1..].iter().any(|(t, _)| t != ";"),
+        None => false,
+    }
+}
+
+/// True for a single SELECT/SHOW/DESCRIBE/EXPLAIN/WITH statement that cannot modify data.
+pub fn is_read_only(sql: &str) -> bool {
+    if has_multiple_statements(sql) {
+        return false;
+    }
+    let w = words(sql);
+    let first = match w.first() {
+        Some((t, _)) => t.as_str(),
+        None => return false,
+    };
+    if !READ_ONLY_KEYWORDS.contains(&first) {
+        return false;
+    }
+    let has_for_update = w.windows(2).any(|p| p[0].0 == "FOR" && p[1].0 == "UPDATE");
+    !has_for_update && !w.iter().any(|(t, _)| WRITE_KEYWORDS.contains(&t.as_str()))
+}
+
+/// Length of `sql` without trailing whitespace, comments and semicolons, so text appended there is not commented out.
+fn code_len(sql: &str) -> usize {
+    let chars: Vec<(usize, char)> = sql.char_indices().collect();
+    let mut end = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let (at, c) = chars[i];
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+        match c {
+            '\'' | '"' | '`' => {
+                i += 1;
+                while i < chars.len() && chars[i].1 != c {
+                    if chars[i].1 == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                end = chars.get(i).map_or(sql.len(), |(at, c)| at + c.len_utf8());
+            }
+            '-' if next == Some('-') => {
+                while i < chars.len() && chars[i].1 != '\n' {
+                    i += 1;
+                }
+            }
+            '#' => {
+                while i < chars.len() && chars[i].1 != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i + 1 < chars.len() && !(chars[i].1 == '*' && chars[i + 1].1 == '/') {
+                    i += 1;
+                }
+                i += 1;
+            }
+            c if c.is_whitespace() || c == ';' => {}
+            c => end = at + c.len_utf8(),
+        }
+        i += 1;
+    }
+    end
+}
+
+/// Append "LIMIT max_rows" to a SELECT/WITH statement without a top-level LIMIT. Returns (sql, injected).
+pub fn inject_limit(sql: &str, max_rows: i64) -> (String, bool) {
+    let w = words(sql);
+    let is_select = matches!(w.first().map(|(t, _)| t.as_str()), Some("SELECT") | Some("WITH"));
+    let has_limit = w.iter().any(|(t, d)| *d == 0 && t == "LIMIT");
+    if !is_select || has_limit {
+        return (sql.to_string(), false);
+    }
+    (format!("{} LIMIT {}", &sql[..code_len(sql)], max_rows.max(1)), true)
+}
+
+fn csv_field(value: &serde_json::Value) -> String {
+    let text = match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if text.contains(',') || text.contains('"') || text.contains('\n') || text.contains('\r') {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+/// RFC 4180 CSV with a header row; starts with a UTF-8 BOM so Excel shows Dari/Pashto text correctly.
+pub fn to_csv(columns: &[String], rows: &[Vec<serde_json::Value>]) -> String {
+    let mut out = String::from('\u{feff}');
+    let header: Vec<String> = columns.iter().map(|c| csv_field(&serde_json::Value::String(c.clone()))).collect();
+    out.push_str(&header.join(","));
+    out.push_str("\r\n");
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(csv_field).collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_only_detection_and_limit_injection() {
+        assert!(is_read_only("SELECT * FROM sales WHERE note = 'delete me';"));
+        assert!(is_read_only("-- drop\nSHOW TABLES"));
+        assert!(!is_read_only("SELECT 1; DELETE FROM sales"));
+        assert!(!is_read_only("WITH x AS (SELECT 1) DELETE FROM sales"));
+        assert!(!is_read_only("SELECT * FROM sales FOR UPDATE"));
+        assert!(!is_read_only("UPDATE sales SET paid_amount = 0"));
+
+        assert_eq!(inject_limit("SELECT * FROM sales;", 500), ("SELECT * FROM sales LIMIT 500".to_string(), true));
+        assert!(!inject_limit("SELECT * FROM (SELECT id FROM sales LIMIT 5) s LIMIT 2", 500).1);
+        assert!(inject_limit("SELECT * FROM (SELECT id FROM sales LIMIT 5) s", 500).1);
+        assert!(!inject_limit("SHOW TABLES", 500).1);
+        // A trailing comment is dropped rather than left to swallow the LIMIT
+        assert_eq!(inject_limit("SELECT * FROM sales -- all of them", 500).0, "SELECT * FROM sales LIMIT 500");
+        assert_eq!(inject_limit("SELECT '--' AS x; /* note */\n# done", 10).0, "SELECT '--' AS x LIMIT 10");
+        assert_eq!(inject_limit("SELECT 1 -- a\nFROM dual", 10).0, "SELECT 1 -- a\nFROM dual LIMIT 10");
+
+        let csv = to_csv(&["name".to_string()], &[vec![serde_json::json!("a,\"b\"")]]);
+        assert_eq!(csv, "\u{feff}name\r\n\"a,\"\"b\"\"\"\r\n");
+    }
+}
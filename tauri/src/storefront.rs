@@ -0,0 +1,273 @@
+//! Online storefront sync over the WooCommerce REST API (v3), which headless shops commonly speak too: products are
+//! pushed with their price, stock and image, and paid orders are pulled back. Requests authenticate with the
+//! store's consumer key and secret over HTTPS.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const API_PATH: &str = "wp-json/wc/v3";
+const REQUEST_TIMEOUT_SECS: u64 = 60;
+/// Orders fetched per page when pulling.
+const ORDERS_PER_PAGE: u32 = 50;
+/// Order statuses that mean the customer has paid.
+pub const PAID_STATUSES: [&str; 2] = ["processing", "completed"];
+
+/// What a push does when the storefront price was edited since the last push.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictRule {
+    /// Overwrite the storefront with the local price.
+    LocalWins,
+    /// Take the storefront price into the local product.
+    RemoteWins,
+    /// Change neither side and flag the product.
+    Flag,
+}
+
+impl ConflictRule {
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        match rule.trim() {
+            "local_wins" => Ok(ConflictRule::LocalWins),
+            "remote_wins" => Ok(ConflictRule::RemoteWins),
+            "flag" => Ok(ConflictRule::Flag),
+            other => Err(format!("Invalid conflict rule: {} (use local_wins, remote_wins or flag)", other)),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConflictRule::LocalWins => "local_wins",
+            ConflictRule::RemoteWins => "remote_wins",
+            ConflictRule::Flag => "flag",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PriceAction {
+    /// Push the local price.
+    Push,
+    /// Copy this storefront price into the local product, then push the rest.
+    Pull(f64),
+    /// Leave the product alone and report a conflict.
+    Conflict,
+}
+
+/// How to treat a product's price given what was last pushed and what the storefront has now. Without an earlier
+/// push, or when the storefront still shows what we pushed, the local price goes out.
+pub fn resolve_price(rule: ConflictRule, last_pushed: Option<f64>, remote: Option<f64>) -> PriceAction {
+    match (last_pushed, remote) {
+        (Some(pushed), Some(remote)) if (pushed - remote).abs() > 0.005 => match rule {
+            ConflictRule::LocalWins => PriceAction::Push,
+            ConflictRule::RemoteWins => PriceAction::Pull(remote),
+            ConflictRule::Flag => PriceAction::Conflict,
+        },
+        _ => PriceAction::Push,
+    }
+}
+
+/// A product as pushed: the price in the store's currency and whole units in stock.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductPush {
+    pub name: String,
+    pub sku: Option<String>,
+    pub price: f64,
+    pub stock: i64,
+    /// Only image URLs; the storefront cannot fetch files from this computer.
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteProduct {
+    pub id: i64,
+    #[serde(default)]
+    pub regular_price: String,
+}
+
+impl RemoteProduct {
+    pub fn price(&self) -> Option<f64> {
+        self.regular_price.trim().parse().ok()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Billing {
+    #[serde(default)]
+    pub first_name: String,
+    #[serde(default)]
+    pub last_name: String,
+    #[serde(default)]
+    pub phone: String,
+    #[serde(default)]
+    pub address_1: String,
+    #[serde(default)]
+    pub city: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteOrderLine {
+    pub product_id: i64,
+    #[serde(default)]
+    pub name: String,
+    pub quantity: f64,
+    /// Unit price after line discounts.
+    #[serde(default)]
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteOrder {
+    pub id: i64,
+    #[serde(default)]
+    pub number: String,
+    pub status: String,
+    #[serde(default)]
+    pub date_created: String,
+    #[serde(default)]
+    pub total: String,
+    #[serde(default)]
+    pub billing: Billing,
+    #[serde(default)]
+    pub line_items: Vec<RemoteOrderLine>,
+}
+
+impl RemoteOrder {
+    pub fn total(&self) -> f64 {
+        self.total.trim().parse().unwrap_or(0.0)
+    }
+
+    pub fn customer_name(&self) -> String {
+        format!("{} {}", self.billing.first_name.trim(), self.billing.last_name.trim()).trim().to_string()
+    }
+}
+
+/// A storefront base URL as stored: https (http only for a shop on this computer), without a trailing slash.
+pub fn normalize_url(url: &str) -> Result<String, String> {
+    let url = url.trim().trim_end_matches('/');
+    let local = url.starts_with("http://localhost") || url.starts_with("http://127.0.0.1");
+    if !(url.starts_with("https://") || local) || url.chars().any(char::is_whitespace) {
+        return Err("Storefront URL must start with https://".to_string());
+    }
+    Ok(url.to_string())
+}
+
+pub struct StorefrontClient {
+    base: String,
+    key: String,
+    secret: String,
+    http: reqwest::blocking::Client,
+}
+
+impl StorefrontClient {
+    pub fn new(url: &str, key: String, secret: String) -> Result<Self, String> {
+        let http = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| format!("HTTP client error: {}", e))?;
+        Ok(StorefrontClient { base: format!("{}/{}", normalize_url(url)?, API_PATH), key, secret, http })
+    }
+
+    fn send(&self, request: reqwest::blocking::RequestBuilder) -> Result<reqwest::blocking::Response, String> {
+        let response = request
+            .basic_auth(&self.key, Some(&self.secret))
+            .send()
+            .map_err(|e| format!("Storefront request failed: {}", e))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err("The storefront refused the API key; check the consumer key and secret".to_string());
+        }
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(format!("Storefront error {}: {}", status, body.chars().take(200).collect::<String>()));
+        }
+        Ok(response)
+    }
+
+    /// The storefront's copy of a product; None when it was deleted there.
+    pub fn product(&self, remote_id: i64) -> Result<Option<RemoteProduct>, String> {
+        match self.send(self.http.get(format!("{}/products/{}", self.base, remote_id))) {
+            Ok(response) => response.json().map(Some).map_err(|e| format!("Invalid storefront response: {}", e)),
+            Err(e) if e.starts_with("Storefront error 404") => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Create the product, or update it when it already has a storefront id. Returns the storefront id.
+    pub fn upsert_product(&self, remote_id: Option<i64>, product: &ProductPush) -> Result<i64, String> {
+        let mut body = serde_json::json!({
+            "name": product.name,
+            "regular_price": format!("{:.2}", product.price),
+            "manage_stock": true,
+            "stock_quantity": product.stock.max(0),
+        });
+        if let Some(sku) = product.sku.as_deref().filter(|s| !s.trim().is_empty()) {
+            body["sku"] = serde_json::Value::from(sku);
+        }
+        if let Some(url) = &product.image_url {
+            body["images"] = serde_json::json!([{ "src": url }]);
+        }
+        let request = match remote_id {
+            Some(id) => self.http.put(format!("{}/products/{}", self.base, id)),
+            None => self.http.post(format!("{}/products", self.base)),
+        };
+        let saved: RemoteProduct = self.send(request.json(&body))?.json().map_err(|e| format!("Invalid storefront response: {}", e))?;
+        Ok(saved.id)
+    }
+
+    /// Paid orders created after `after` (an ISO 8601 time), oldest first.
+    pub fn paid_orders(&self, after: Option<&str>) -> Result<Vec<RemoteOrder>, String> {
+        let mut orders = Vec::new();
+        for page in 1.. {
+            let mut query = vec![
+                ("status".to_string(), PAID_STATUSES.join(",")),
+                ("orderby".to_string(), "date".to_string()),
+                ("order".to_string(), "asc".to_string()),
+                ("per_page".to_string(), ORDERS_PER_PAGE.to_string()),
+                ("page".to_string(), page.to_string()),
+            ];
+            if let Some(after) = after {
+                query.push(("after".to_string(), after.to_string()));
+            }
+            let batch: Vec<RemoteOrder> = self
+                .send(self.http.get(format!("{}/orders", self.base)).query(&query))?
+                .json()
+                .map_err(|e| format!("Invalid storefront response: {}", e))?;
+            let last = batch.len() < ORDERS_PER_PAGE as usize;
+            orders.extend(batch);
+            if last {
+                break;
+            }
+        }
+        Ok(orders)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storefront_rules() {
+        assert_eq!(resolve_price(ConflictRule::Flag, None, Some(99.0)), PriceAction::Push);
+        assert_eq!(resolve_price(ConflictRule::Flag, Some(100.0), Some(100.0)), PriceAction::Push);
+        assert_eq!(resolve_price(ConflictRule::Flag, Some(100.0), Some(90.0)), PriceAction::Conflict);
+        assert_eq!(resolve_price(ConflictRule::RemoteWins, Some(100.0), Some(90.0)), PriceAction::Pull(90.0));
+        assert_eq!(resolve_price(ConflictRule::LocalWins, Some(100.0), Some(90.0)), PriceAction::Push);
+        assert_eq!(ConflictRule::parse("remote_wins"), Ok(ConflictRule::RemoteWins));
+        assert!(ConflictRule::parse("newest").is_err());
+
+        assert_eq!(normalize_url(" https://shop.example.af/ "), Ok("https://shop.example.af".to_string()));
+        assert!(normalize_url("http://shop.example.af").is_err());
+        assert!(normalize_url("http://localhost:8080").is_ok());
+
+        let order: RemoteOrder = serde_json::from_str(
+            r#"{"id": 71, "number": "71", "status": "processing", "date_created": "2024-02-01T10:30:00", "total": "250.00",
+                "billing": {"first_name": "Mina", "last_name": "Rahimi", "phone": "0799000000"},
+                "line_items": [{"product_id": 15, "name": "Tea 500g", "quantity": 2, "price": 125}]}"#,
+        )
+        .unwrap();
+        assert_eq!(order.total(), 250.0);
+        assert_eq!(order.customer_name(), "Mina Rahimi");
+        assert_eq!(order.line_items[0].quantity, 2.0);
+    }
+}
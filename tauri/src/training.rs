@@ -0,0 +1,37 @@
+//! Training mode: while it is on, every connection the app opens (commands, backups, restores) goes to a separate
+//! training database instead of the live one, so new staff can practice without touching the real books.
+
+use std::sync::Mutex;
+
+static DATABASE: Mutex<Option<String>> = Mutex::new(None);
+
+/// The training database in use, or None when the app works on the live database.
+pub fn database() -> Option<String> {
+    DATABASE.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn set_database(name: Option<String>) {
+    *DATABASE.lock().unwrap_or_else(|e| e.into_inner()) = name;
+}
+
+/// Name of the training database of `live`; MySQL names are at most 64 characters, so long names are cut.
+pub fn database_name(live: &str) -> String {
+    const SUFFIX: &str = "_training";
+    let stem: String = live.chars().take(64 - SUFFIX.len()).collect();
+    format!("{}{}", stem, SUFFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_name() {
+        assert_eq!(database_name("tauri_app"), "tauri_app_training");
+        assert_eq!(database_name(&"x".repeat(80)).len(), 64);
+        assert_eq!(database(), None);
+        set_database(Some("shop_training".to_string()));
+        assert_eq!(database().as_deref(), Some("shop_training"));
+        set_database(None);
+    }
+}